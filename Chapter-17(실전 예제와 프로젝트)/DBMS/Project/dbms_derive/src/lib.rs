@@ -0,0 +1,258 @@
+//! `#[derive(DItem)]`: DItem 구현, 필드 직렬화/복원, ItemFactory 등록 함수 생성
+//!
+//! ```ignore
+//! #[derive(Debug, DItem)]
+//! #[ditem(item_type = 100, table_type = 10, version = 2)]
+//! struct Edge {
+//!     #[key]
+//!     id: i32,
+//!     length: f64,
+//! }
+//! ```
+//!
+//! `#[guid]` 를 붙인 Guid 필드는 DItem::guid 가 됩니다 (null 이면 None).
+//! `#[key]` 가 i32 가 아니면 (i64, String, Guid, 튜플 등 `TableKey`) DItem::table_key 로
+//! 그 값을 돌려주고, DItem::key 는 식별자로 쓰이지 않도록 항상 0 입니다.
+//! 모든 필드는 `dbms::tx_stream::StreamValue` 를 구현해야 하고,
+//! 키가 아닌 필드는 `Default` 로 생성 시 초기화됩니다.
+//! 자료형이 bool, i32, i64, f64, String, Guid, `Vec<u8>` 인 필드는 `ItemSchema` 로 등록되어
+//! 이름으로 읽고 쓸 수 있습니다 (키와 `#[guid]` 필드는 읽기 전용).
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::{Data, DeriveInput, Error, Fields, GenericArgument, Ident, LitInt, PathArguments, Type, parse_macro_input};
+
+#[proc_macro_derive(DItem, attributes(ditem, key, guid))]
+pub fn derive_ditem(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input).unwrap_or_else(|e| e.to_compile_error()).into()
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let name = &input.ident;
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(&input.generics, "DItem derive does not support generics"));
+    }
+
+    let (item_type, table_type, version) = type_ids(input)?;
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => return Err(Error::new_spanned(name, "DItem derive requires named fields")),
+        },
+        _ => return Err(Error::new_spanned(name, "DItem derive only supports structs")),
+    };
+
+    let mut key: Option<(&Ident, &Type)> = None;
+    for field in fields {
+        if field.attrs.iter().any(|a| a.path().is_ident("key")) {
+            if key.is_some() {
+                return Err(Error::new_spanned(field, "only one field can be marked #[key]"));
+            }
+            key = field.ident.as_ref().map(|ident| (ident, &field.ty));
+        }
+    }
+    let mut guid: Option<&Ident> = None;
+    for field in fields {
+        if field.attrs.iter().any(|a| a.path().is_ident("guid")) {
+            if guid.is_some() {
+                return Err(Error::new_spanned(field, "only one field can be marked #[guid]"));
+            }
+            guid = field.ident.as_ref();
+        }
+    }
+    let guid_fn = guid.map(|guid| {
+        quote! {
+            fn guid(&self) -> Option<::dbms::guid::Guid> {
+                (!self.#guid.is_null()).then(|| self.#guid.clone())
+            }
+        }
+    });
+
+    let Some((key, key_type)) = key else {
+        return Err(Error::new(Span::call_site(), "DItem derive requires a #[key] field"));
+    };
+    let int_key = matches!(key_type, Type::Path(path) if path.qself.is_none() && path.path.is_ident("i32"));
+
+    // i32 키는 그대로, 그 외 키는 table_key 로만 전달 (key() 는 0)
+    let (key_fn, table_key_fn, register_type) = if int_key {
+        (
+            quote! { self.#key },
+            None,
+            quote! {
+                factory.register_type(
+                    #item_type,
+                    #table_type,
+                    ::std::sync::Arc::new(|key| ::std::sync::Arc::new(#name::with_key(key))),
+                    ::std::sync::Arc::new(|_item| {}),
+                )
+            },
+        )
+    } else {
+        (
+            quote! { 0 },
+            Some(quote! {
+                fn table_key(&self) -> Option<&dyn ::std::any::Any> {
+                    Some(&self.#key)
+                }
+            }),
+            // i32 키로는 만들 수 없으므로 생성 콜백 없이 등록 (create_item, Table::insert 는 None)
+            quote! {
+                factory.register_keyed_type(#item_type, #table_type, ::std::sync::Arc::new(|_item| {}))
+            },
+        )
+    };
+
+    let idents: Vec<&Ident> = fields.iter().filter_map(|f| f.ident.as_ref()).collect();
+    let defaults = idents.iter().filter(|i| *i != &key).map(|i| quote! { #i: ::core::default::Default::default() });
+
+    // FieldValue 로 나타낼 수 있는 필드만 스키마에 포함
+    let mut settable = false;
+    let schema_fields: Vec<_> = fields.iter().filter(|f| reflectable(&f.ty)).filter_map(|f| {
+        let ident = f.ident.as_ref()?;
+        let ty = &f.ty;
+        let field_name = ident.to_string();
+        let field_type = quote! { <#ty as ::dbms::reflect::ReflectValue>::FIELD_TYPE };
+        let get = quote! { |item: &#name| ::dbms::reflect::ReflectValue::to_field(&item.#ident) };
+        Some(if ident == key || guid == Some(ident) {
+            quote! { .read_only::<#name>(#field_name, #field_type, #get) }
+        } else {
+            settable = true;
+            quote! {
+                .field::<#name>(#field_name, #field_type, #get, |item: &#name, value| {
+                    let mut copy = item.reflect_copy()?;
+                    copy.#ident = ::dbms::reflect::ReflectValue::from_field(value)?;
+                    Some(copy)
+                })
+            }
+        })
+    }).collect();
+    let reflect_copy = settable.then(|| {
+        quote! {
+            /// 필드를 바꾼 아이템을 만들 때 쓰는 복사본 (직렬화 후 다시 읽음)
+            fn reflect_copy(&self) -> Option<Self> {
+                let mut stream = ::dbms::tx_stream::MemTxStream::new();
+                #( ::dbms::tx_stream::StreamValue::write_to(&self.#idents, &mut stream); )*
+                #name::deserialize(&mut ::dbms::tx_stream::MemTxStream::from_bytes(stream.into_bytes()))
+            }
+        }
+    });
+
+    Ok(quote! {
+        impl ::dbms::item::DItem for #name {
+            fn key(&self) -> i32 {
+                #key_fn
+            }
+
+            fn item_type(&self) -> u16 {
+                #item_type
+            }
+
+            fn table_type(&self) -> u16 {
+                #table_type
+            }
+
+            fn serialize(&self, stream: &mut dyn ::dbms::tx_stream::TxStream, _session: &::dbms::session::Session) {
+                #( ::dbms::tx_stream::StreamValue::write_to(&self.#idents, stream); )*
+            }
+
+            fn schema_version(&self) -> u16 {
+                #version
+            }
+
+            #guid_fn
+
+            #table_key_fn
+        }
+
+        impl #name {
+            pub const ITEM_TYPE: u16 = #item_type;
+            pub const TABLE_TYPE: u16 = #table_type;
+
+            /// serialize 로 기록한 필드를 같은 순서로 읽음
+            pub fn deserialize(stream: &mut dyn ::dbms::tx_stream::TxStream) -> Option<Self> {
+                Some(#name {
+                    #( #idents: ::dbms::tx_stream::StreamValue::read_from(stream)?, )*
+                })
+            }
+
+            /// 키만 채운 새 아이템 (나머지 필드는 Default)
+            pub fn with_key(key: #key_type) -> Self {
+                #name {
+                    #key: key,
+                    #( #defaults, )*
+                }
+            }
+
+            /// 값 자료형이 FieldValue 에 맞는 필드의 스키마 (register 가 함께 등록)
+            pub fn schema() -> ::dbms::reflect::ItemSchema {
+                ::dbms::reflect::ItemSchema::new() #( #schema_fields )*
+            }
+
+            #reflect_copy
+
+            /// ItemFactory 에 생성/소멸/복원 콜백, 스키마 버전과 필드 스키마 등록
+            /// (i32 가 아닌 키는 생성 콜백 없이 등록되므로 Table::insert_item 으로 삽입)
+            pub fn register(factory: &::std::sync::Mutex<::dbms::item_factory::ItemFactory>) -> bool {
+                let Ok(mut factory) = factory.lock() else {
+                    return false;
+                };
+                #register_type && factory.register_deserializer(
+                    #item_type,
+                    ::std::sync::Arc::new(|_key, stream| {
+                        #name::deserialize(stream).map(|item| ::std::sync::Arc::new(item) as ::std::sync::Arc<dyn ::dbms::item::DItem>)
+                    }),
+                ) && factory.set_schema_version(#item_type, #version)
+                    && factory.register_schema(#item_type, #name::schema())
+            }
+        }
+    })
+}
+
+/// ReflectValue 를 구현한 자료형인지 (이름으로만 판단: bool, i32, i64, f64, String, Guid, Vec<u8>)
+fn reflectable(ty: &Type) -> bool {
+    let Type::Path(path) = ty else {
+        return false;
+    };
+    let Some(last) = path.path.segments.last().filter(|_| path.qself.is_none()) else {
+        return false;
+    };
+    match &last.arguments {
+        PathArguments::None => ["bool", "i32", "i64", "f64", "String", "Guid"].iter().any(|n| last.ident == n),
+        PathArguments::AngleBracketed(args) if last.ident == "Vec" => matches!(
+            args.args.first(),
+            Some(GenericArgument::Type(Type::Path(inner))) if args.args.len() == 1 && inner.path.is_ident("u8")
+        ),
+        _ => false,
+    }
+}
+
+/// #[ditem(item_type = N, table_type = M, version = V)] 읽기 (version 은 생략 시 1)
+fn type_ids(input: &DeriveInput) -> syn::Result<(u16, u16, u16)> {
+    let mut item_type = None;
+    let mut table_type = None;
+    let mut version = 1;
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("ditem")) {
+        attr.parse_nested_meta(|meta| {
+            let value: LitInt = meta.value()?.parse()?;
+            if meta.path.is_ident("item_type") {
+                item_type = Some(value.base10_parse::<u16>()?);
+            } else if meta.path.is_ident("table_type") {
+                table_type = Some(value.base10_parse::<u16>()?);
+            } else if meta.path.is_ident("version") {
+                version = value.base10_parse::<u16>()?;
+            } else {
+                return Err(meta.error("expected `item_type`, `table_type` or `version`"));
+            }
+            Ok(())
+        })?;
+    }
+    match (item_type, table_type) {
+        (Some(0), _) | (_, Some(0)) => Err(Error::new_spanned(&input.ident, "item_type and table_type must be non-zero")),
+        _ if version == 0 => Err(Error::new_spanned(&input.ident, "version must be non-zero")),
+        (Some(i), Some(t)) => Ok((i, t, version)),
+        _ => Err(Error::new_spanned(&input.ident, "missing #[ditem(item_type = .., table_type = ..)]")),
+    }
+}
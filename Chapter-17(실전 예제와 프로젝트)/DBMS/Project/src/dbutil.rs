@@ -3,7 +3,7 @@ use std::fmt::Arguments;
 use std::path::PathBuf;
 
 /// 문자열 포맷: printf 스타일
-pub fn format_string(format: &str, args: impl std::fmt::Display) -> String {
+pub fn format_string(_format: &str, args: impl std::fmt::Display) -> String {
     format!("{}", args)
 }
 
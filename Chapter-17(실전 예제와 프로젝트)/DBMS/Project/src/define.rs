@@ -1,4 +1,5 @@
 use crate::item::Cursor;
+use crate::key::TableKey;
 
 // 트랜잭션 상태 플래그
 #[derive(Clone, Debug)]
@@ -12,6 +13,39 @@ pub enum TxAction {
     Cancelled, // 트랜잭션이 되돌려진 후 상태 초기화용
 }
 
+impl TxAction {
+    /// 액션 대상 키 (Cancelled 는 None)
+    pub fn key(&self) -> Option<i32> {
+        match self {
+            TxAction::Insert(c) | TxAction::Remove(c) => Some(c.key()),
+            TxAction::Modify { after, .. } => Some(after.key()),
+            TxAction::Cancelled => None,
+        }
+    }
+
+    /// 키 타입 K 의 대상 키
+    pub fn key_as<K: TableKey>(&self) -> Option<K> {
+        match self {
+            TxAction::Insert(c) | TxAction::Remove(c) => c.key_as(),
+            TxAction::Modify { after, .. } => after.key_as(),
+            TxAction::Cancelled => None,
+        }
+    }
+
+    /// 역방향 액션: undo 시 적용할 액션
+    pub fn inverse(&self) -> TxAction {
+        match self {
+            TxAction::Insert(c) => TxAction::Remove(c.clone()),
+            TxAction::Remove(c) => TxAction::Insert(c.clone()),
+            TxAction::Modify { before, after } => TxAction::Modify {
+                before: after.clone(),
+                after: before.clone(),
+            },
+            TxAction::Cancelled => TxAction::Cancelled,
+        }
+    }
+}
+
 // 시스템 제한값
 pub const MAX_TABLE: usize = 256;
 pub const MAX_ITEM_TYPE: usize = 1024;
@@ -0,0 +1,101 @@
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use crate::guid::Guid;
+    use crate::item::DItem;
+    use crate::item_factory::item_factory_mut;
+    use crate::query::Field;
+    use crate::reflect::FieldValue;
+    use crate::session::Session;
+    use crate::tx_stream::MemTxStream;
+
+    #[derive(Debug, PartialEq, DItem)]
+    #[ditem(item_type = 1500, table_type = 150)]
+    struct Vertex {
+        #[key]
+        id: i32,
+        name: String,
+        position: Vec<f64>,
+        owner: Option<Guid>,
+        locked: bool,
+    }
+
+    #[test]
+    fn test_derived_item_serializes_and_registers() {
+        assert_eq!((Vertex::ITEM_TYPE, Vertex::TABLE_TYPE), (1500, 150));
+        let vertex = Vertex {
+            id: 7,
+            name: "v7".to_string(),
+            position: vec![1.0, -2.5, 3.25],
+            owner: Some(Guid::new()),
+            locked: true,
+        };
+        assert_eq!((vertex.key(), vertex.item_type(), vertex.table_type()), (7, 1500, 150));
+
+        let session = Session::new();
+        let mut stream = MemTxStream::new();
+        vertex.serialize(&mut stream, &session);
+        let mut stream = MemTxStream::from_bytes(stream.into_bytes());
+        assert_eq!(Vertex::deserialize(&mut stream), Some(vertex));
+        assert_eq!(stream.remaining(), 0);
+
+        // 등록 후 팩토리로 생성 (키 외 필드는 Default), 중복 등록은 거부
+        let factory = item_factory_mut();
+        assert!(Vertex::register(factory));
+        assert!(!Vertex::register(factory));
+        let mut session = Session::new();
+        session.register_table(150, 1500);
+        let cursor = session.get_table_mut(150).unwrap().insert(3, factory).unwrap();
+        assert_eq!(cursor.data.downcast_ref::<Vertex>(), Some(&Vertex::with_key(3)));
+    }
+
+    #[derive(Debug, PartialEq, DItem)]
+    #[ditem(item_type = 2300, table_type = 230)]
+    struct Beam {
+        #[key]
+        id: i32,
+        #[guid]
+        uid: Guid,
+        name: String,
+        length: f64,
+        data: Vec<u8>,
+        tags: Vec<i32>, // FieldValue 로 나타낼 수 없어 스키마에서 제외
+    }
+
+    #[test]
+    fn test_derived_schema_supports_reflection() {
+        let factory = item_factory_mut();
+        assert!(Beam::register(factory));
+        assert_eq!(Beam::schema().names(), ["id", "uid", "name", "length", "data"]);
+
+        let beam = Beam {
+            id: 1,
+            uid: Guid::new(),
+            name: "main".to_string(),
+            length: 2.5,
+            data: vec![1, 2],
+            tags: vec![7],
+        };
+        let f = factory.lock().unwrap();
+        assert_eq!(f.get_field(&beam, "length"), Some(FieldValue::Float(2.5)));
+        assert_eq!(f.get_field(&beam, "tags"), None);
+
+        // 쓰기는 나머지 필드를 그대로 둔 새 아이템, 키와 GUID 는 읽기 전용
+        let renamed = f.set_field(&beam, "name", FieldValue::from("side")).unwrap();
+        assert_eq!(renamed.downcast_ref::<Beam>(), Some(&Beam { name: "side".to_string(), uid: beam.uid.clone(), data: vec![1, 2], tags: vec![7], ..beam }));
+        assert!(f.set_field(renamed.as_ref(), "id", FieldValue::Int(2)).is_none());
+        assert!(f.set_field(renamed.as_ref(), "uid", FieldValue::Guid(Guid::new())).is_none());
+        assert!(f.set_field(renamed.as_ref(), "length", FieldValue::from("long")).is_none());
+        drop(f);
+
+        // 손으로 만든 스키마 없이 Field::reflected 로 조회
+        let mut session = Session::new();
+        session.register_table(230, 2300);
+        let table = session.get_table_mut(230).unwrap();
+        for (id, name) in [(1, "main"), (2, "side")] {
+            table.insert_item(Arc::new(Beam { id, name: name.to_string(), ..Beam::with_key(id) }));
+        }
+        let name = Field::reflected(2300, "name").unwrap();
+        assert_eq!(session.query(230).where_eq(&name, "side").keys(), vec![2]);
+    }
+}
@@ -1,6 +1,11 @@
-use rand::Rng;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
 
-#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+/// GUID (RFC 9562 UUID): 필드 구성은 Windows/C++ GUID 구조체와 같음
+/// 필드 순서대로 비교하므로 Ord 는 big-endian 바이트 순서와 같음 (v7 은 생성 시각 순)
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
 pub struct Guid {
     pub data1: u32,
     pub data2: u16,
@@ -8,44 +13,203 @@ pub struct Guid {
     pub data4: [u8; 8],
 }
 
+/// 문자열 형식
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GuidFormat {
+    Any,      // 아래 모두 허용
+    Braced,   // {XXXXXXXX-XXXX-XXXX-XXXX-XXXXXXXXXXXX}
+    Unbraced, // XXXXXXXX-XXXX-XXXX-XXXX-XXXXXXXXXXXX
+    Simple,   // 하이픈 없는 32 자리
+}
+
+/// GUID 문자열 해석 실패
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GuidParseError {
+    pub message: String,
+}
+
+impl fmt::Display for GuidParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid GUID: {}", self.message)
+    }
+}
+
+impl std::error::Error for GuidParseError {}
+
+// v7 단조 증가용: 마지막 (밀리초, 12비트 순번)
+static LAST_V7: Mutex<(u64, u16)> = Mutex::new((0, 0));
+
 impl Guid {
-    /// 생성: 시간 + 랜덤 기반 GUID
+    /// 이름 기반 GUID 용 표준 네임스페이스 (RFC 9562 부록 A)
+    pub const NAMESPACE_DNS: Guid = Guid::from_fields(0x6ba7b810, 0x9dad, 0x11d1, [0x80, 0xb4, 0x00, 0xc0, 0x4f, 0xd4, 0x30, 0xc8]);
+    pub const NAMESPACE_URL: Guid = Guid::from_fields(0x6ba7b811, 0x9dad, 0x11d1, [0x80, 0xb4, 0x00, 0xc0, 0x4f, 0xd4, 0x30, 0xc8]);
+    pub const NAMESPACE_OID: Guid = Guid::from_fields(0x6ba7b812, 0x9dad, 0x11d1, [0x80, 0xb4, 0x00, 0xc0, 0x4f, 0xd4, 0x30, 0xc8]);
+    pub const NAMESPACE_X500: Guid = Guid::from_fields(0x6ba7b814, 0x9dad, 0x11d1, [0x80, 0xb4, 0x00, 0xc0, 0x4f, 0xd4, 0x30, 0xc8]);
+
+    /// 생성: 랜덤 기반 GUID (v4)
     pub fn new() -> Self {
+        Self::new_v4()
+    }
+
+    pub const fn from_fields(data1: u32, data2: u16, data3: u16, data4: [u8; 8]) -> Self {
+        Guid { data1, data2, data3, data4 }
+    }
+
+    /// 랜덤 (v4)
+    pub fn new_v4() -> Self {
+        use rand::Rng;
+        let bytes: [u8; 16] = rand::thread_rng().r#gen();
+        Self::with_version(bytes, 4)
+    }
+
+    /// 시간 순서 (v7): 48비트 Unix 밀리초 + 12비트 순번 + 62비트 랜덤
+    /// 같은 밀리초 안에서도 순번이 증가하므로 한 프로세스에서 만든 값은 생성 순으로 정렬됨
+    pub fn new_v7() -> Self {
         use rand::Rng;
         let mut rng = rand::thread_rng();
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or(0);
+
+        let (millis, seq) = {
+            let mut last = LAST_V7.lock().unwrap_or_else(|e| e.into_inner());
+            if now > last.0 {
+                *last = (now, rng.gen_range(0..0x800)); // 위쪽 절반은 같은 밀리초의 증가분으로 남김
+            } else if last.1 < 0xFFF {
+                last.1 += 1;
+            } else {
+                *last = (last.0 + 1, 0); // 순번이 넘치면 다음 밀리초를 미리 사용
+            }
+            *last
+        };
+
+        let mut bytes: [u8; 16] = rng.r#gen();
+        bytes[..6].copy_from_slice(&millis.to_be_bytes()[2..]);
+        bytes[6] = (seq >> 8) as u8;
+        bytes[7] = seq as u8;
+        Self::with_version(bytes, 7)
+    }
+
+    /// 이름 기반 (v5, SHA-1): 같은 네임스페이스와 이름이면 항상 같은 값
+    pub fn new_v5(namespace: &Guid, name: &[u8]) -> Self {
+        let mut hasher = sha1_smol::Sha1::new();
+        hasher.update(&namespace.to_bytes_be());
+        hasher.update(name);
+        let digest = hasher.digest().bytes();
+        let mut bytes = [0u8; 16];
+        bytes.copy_from_slice(&digest[..16]);
+        Self::with_version(bytes, 5)
+    }
+
+    /// 버전(상위 4비트)과 RFC variant(10xx) 비트 설정
+    fn with_version(mut bytes: [u8; 16], version: u8) -> Self {
+        bytes[6] = (bytes[6] & 0x0F) | (version << 4);
+        bytes[8] = (bytes[8] & 0x3F) | 0x80;
+        Self::from_bytes_be(bytes)
+    }
+
+    /// 버전 번호 (4, 5, 7 등)
+    pub fn version(&self) -> u8 {
+        (self.data3 >> 12) as u8
+    }
+
+    /// RFC 9562 variant 여부 (10xx)
+    pub fn is_rfc_variant(&self) -> bool {
+        self.data4[0] & 0xC0 == 0x80
+    }
+
+    /// v7 에 기록된 Unix 밀리초
+    pub fn timestamp_millis(&self) -> Option<u64> {
+        if self.version() != 7 {
+            return None;
+        }
+        Some(((self.data1 as u64) << 16) | self.data2 as u64)
+    }
+
+    /// RFC 바이트 순서 (모든 필드 big-endian)
+    pub fn to_bytes_be(&self) -> [u8; 16] {
+        let mut bytes = [0u8; 16];
+        bytes[0..4].copy_from_slice(&self.data1.to_be_bytes());
+        bytes[4..6].copy_from_slice(&self.data2.to_be_bytes());
+        bytes[6..8].copy_from_slice(&self.data3.to_be_bytes());
+        bytes[8..].copy_from_slice(&self.data4);
+        bytes
+    }
+
+    pub fn from_bytes_be(bytes: [u8; 16]) -> Self {
         Guid {
-            data1: rng.r#gen(),
-            data2: rng.r#gen(),
-            data3: rng.r#gen(),
-            data4: rng.r#gen(),
+            data1: u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+            data2: u16::from_be_bytes([bytes[4], bytes[5]]),
+            data3: u16::from_be_bytes([bytes[6], bytes[7]]),
+            data4: [bytes[8], bytes[9], bytes[10], bytes[11], bytes[12], bytes[13], bytes[14], bytes[15]],
         }
     }
 
-    /// 문자열 변환: "XXXXXXXX-XXXX-XXXX-XXXX-XXXXXXXXXXXX"
-    pub fn to_string(&self) -> String {
-        format!(
-            "{:08X}-{:04X}-{:04X}-{:02X}{:02X}-{:02X}{:02X}{:02X}{:02X}{:02X}{:02X}",
-            self.data1, self.data2, self.data3,
-            self.data4[0], self.data4[1],
-            self.data4[2], self.data4[3], self.data4[4], self.data4[5], self.data4[6], self.data4[7]
-        )
+    /// Windows/C++ GUID 메모리 순서 (data1~data3 little-endian, data4 그대로)
+    pub fn to_bytes_le(&self) -> [u8; 16] {
+        let mut bytes = [0u8; 16];
+        bytes[0..4].copy_from_slice(&self.data1.to_le_bytes());
+        bytes[4..6].copy_from_slice(&self.data2.to_le_bytes());
+        bytes[6..8].copy_from_slice(&self.data3.to_le_bytes());
+        bytes[8..].copy_from_slice(&self.data4);
+        bytes
+    }
+
+    pub fn from_bytes_le(bytes: [u8; 16]) -> Self {
+        Guid {
+            data1: u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+            data2: u16::from_le_bytes([bytes[4], bytes[5]]),
+            data3: u16::from_le_bytes([bytes[6], bytes[7]]),
+            data4: [bytes[8], bytes[9], bytes[10], bytes[11], bytes[12], bytes[13], bytes[14], bytes[15]],
+        }
+    }
+
+    /// 중괄호 형식: "{XXXXXXXX-XXXX-XXXX-XXXX-XXXXXXXXXXXX}" (C++ 도구와 같은 표기)
+    pub fn to_braced_string(&self) -> String {
+        format!("{:#}", self)
     }
 
-    /// 문자열 → GUID 변환
+    /// 문자열 → GUID 변환 (중괄호/하이픈 유무 모두 허용)
     pub fn from_string(s: &str) -> Option<Self> {
-        let clean: String = s.chars().filter(|c| c.is_ascii_hexdigit()).collect();
-        if clean.len() != 32 { return None; }
+        Self::parse(s, GuidFormat::Any).ok()
+    }
+
+    /// 지정한 형식으로만 해석 (대소문자 무관, 앞뒤 공백 허용)
+    pub fn parse(s: &str, format: GuidFormat) -> Result<Self, GuidParseError> {
+        let s = s.trim();
+        let braced = s.starts_with('{') && s.ends_with('}') && s.len() >= 2;
+        let body = if braced { &s[1..s.len() - 1] } else { s };
+        let hyphenated = body.len() == 36;
+
+        let allowed = match format {
+            GuidFormat::Any => !braced || hyphenated,
+            GuidFormat::Braced => braced && hyphenated,
+            GuidFormat::Unbraced => !braced && hyphenated,
+            GuidFormat::Simple => !braced && body.len() == 32,
+        };
+        if !allowed {
+            return Err(parse_error(format!("{:?} is not in {:?} format", s, format)));
+        }
+
+        let hex: String = if hyphenated {
+            let groups: Vec<&str> = body.split('-').collect();
+            let lengths: Vec<usize> = groups.iter().map(|g| g.len()).collect();
+            if lengths != [8, 4, 4, 4, 12] {
+                return Err(parse_error(format!("{:?} must have 8-4-4-4-12 hex groups", s)));
+            }
+            groups.concat()
+        } else if body.len() == 32 {
+            body.to_string()
+        } else {
+            return Err(parse_error(format!("{:?} has the wrong length", s)));
+        };
 
-        let parse = |i| u8::from_str_radix(&clean[i..i+2], 16).ok();
-        Some(Guid {
-            data1: u32::from_str_radix(&clean[0..8], 16).ok()?,
-            data2: u16::from_str_radix(&clean[8..12], 16).ok()?,
-            data3: u16::from_str_radix(&clean[12..16], 16).ok()?,
-            data4: [
-                parse(16)?, parse(18)?, parse(20)?, parse(22)?,
-                parse(24)?, parse(26)?, parse(28)?, parse(30)?
-            ],
-        })
+        let mut bytes = [0u8; 16];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = hex
+                .get(i * 2..i * 2 + 2)
+                .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+                .ok_or_else(|| parse_error(format!("{:?} contains a non-hex digit", s)))?;
+        }
+        Ok(Self::from_bytes_be(bytes))
     }
 
     /// Null GUID
@@ -64,4 +228,37 @@ impl Guid {
     }
 }
 
+/// "XXXXXXXX-XXXX-XXXX-XXXX-XXXXXXXXXXXX" (대문자), {:#} 이면 중괄호 포함
+impl fmt::Display for Guid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = format!(
+            "{:08X}-{:04X}-{:04X}-{:02X}{:02X}-{:02X}{:02X}{:02X}{:02X}{:02X}{:02X}",
+            self.data1, self.data2, self.data3,
+            self.data4[0], self.data4[1],
+            self.data4[2], self.data4[3], self.data4[4], self.data4[5], self.data4[6], self.data4[7]
+        );
+        if f.alternate() {
+            write!(f, "{{{}}}", text)
+        } else {
+            f.write_str(&text)
+        }
+    }
+}
+
+impl FromStr for Guid {
+    type Err = GuidParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s, GuidFormat::Any)
+    }
+}
+
+impl Default for Guid {
+    fn default() -> Self {
+        Guid::null()
+    }
+}
 
+fn parse_error(message: String) -> GuidParseError {
+    GuidParseError { message }
+}
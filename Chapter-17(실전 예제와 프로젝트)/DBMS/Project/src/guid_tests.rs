@@ -0,0 +1,156 @@
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use crate::dbutil::get_db_temp_path;
+    use crate::guid::{Guid, GuidFormat};
+    use crate::item::DItem;
+    use crate::item_factory::item_factory_mut;
+    use crate::session::Session;
+
+    #[derive(Debug, DItem)]
+    #[ditem(item_type = 1800, table_type = 180)]
+    struct Body {
+        #[key]
+        id: i32,
+        #[guid]
+        uid: Guid,
+    }
+
+    #[test]
+    fn test_guid_versions_and_ordering() {
+        let v4 = Guid::new();
+        assert_eq!(v4.version(), 4);
+        assert!(v4.is_rfc_variant());
+
+        // RFC 예제와 같은 값 (Python uuid.uuid5 와 동일)
+        let v5 = Guid::new_v5(&Guid::NAMESPACE_DNS, b"www.example.com");
+        assert_eq!(v5.to_string(), "2ED6657D-E927-568B-95E1-2665A8AEA6A2");
+        assert_eq!((v5.version(), v5.is_rfc_variant()), (5, true));
+
+        // v7 은 생성 순서대로 정렬됨
+        let generated: Vec<Guid> = (0..2000).map(|_| Guid::new_v7()).collect();
+        let mut sorted = generated.clone();
+        sorted.sort();
+        assert_eq!(sorted, generated);
+        let first = &generated[0];
+        assert_eq!((first.version(), first.is_rfc_variant()), (7, true));
+        let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_millis() as u64;
+        assert!(now.abs_diff(first.timestamp_millis().unwrap()) < 10_000);
+    }
+
+    #[test]
+    fn test_guid_text_and_byte_layouts() {
+        let guid: Guid = "{00112233-4455-6677-8899-aabbccddeeff}".parse().unwrap();
+        assert_eq!(guid.to_bytes_be(), [0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88, 0x99, 0xAA, 0xBB, 0xCC, 0xDD, 0xEE, 0xFF]);
+        // C++ GUID 구조체 메모리 순서
+        let le = [0x33, 0x22, 0x11, 0x00, 0x55, 0x44, 0x77, 0x66, 0x88, 0x99, 0xAA, 0xBB, 0xCC, 0xDD, 0xEE, 0xFF];
+        assert_eq!(guid.to_bytes_le(), le);
+        assert_eq!(Guid::from_bytes_le(le), guid);
+        assert_eq!(Guid::from_bytes_be(guid.to_bytes_be()), guid);
+
+        assert_eq!(guid.to_string(), "00112233-4455-6677-8899-AABBCCDDEEFF");
+        assert_eq!(guid.to_braced_string(), "{00112233-4455-6677-8899-AABBCCDDEEFF}");
+        assert_eq!(Guid::from_string(&guid.to_string()), Some(guid.clone()));
+
+        let text = "00112233-4455-6677-8899-AABBCCDDEEFF";
+        assert!(Guid::parse(text, GuidFormat::Unbraced).is_ok());
+        assert!(Guid::parse(text, GuidFormat::Braced).is_err());
+        assert!(Guid::parse(&format!("{{{}}}", text), GuidFormat::Unbraced).is_err());
+        assert_eq!(Guid::parse("00112233445566778899aabbccddeeff", GuidFormat::Simple), Ok(guid));
+        assert!("00112233-4455-6677-8899-AABBCCDDEEFG".parse::<Guid>().is_err());
+        assert!("0011223-34455-6677-8899-AABBCCDDEEFF".parse::<Guid>().is_err());
+    }
+
+    #[derive(Debug, DItem)]
+    #[ditem(item_type = 1801, table_type = 181)]
+    struct Part {
+        #[key]
+        id: i32,
+        #[guid]
+        uid: Guid,
+    }
+
+    #[derive(Debug, DItem)]
+    #[ditem(item_type = 1802, table_type = 182)]
+    struct Tag {
+        #[key]
+        id: i32,
+        #[guid]
+        uid: Guid,
+    }
+
+    #[test]
+    fn test_duplicate_guids_are_rejected_across_tables() {
+        let factory = item_factory_mut();
+        assert!(Part::register(factory));
+        assert!(Tag::register(factory));
+
+        let uid = Guid::new_v7();
+        let mut session = Session::new();
+        session.register_table(181, 1801);
+        session.register_table(182, 1802);
+        let parts = session.get_table_mut(181).unwrap();
+        assert!(parts.insert_item(Arc::new(Part { id: 1, uid: uid.clone() })).is_some());
+        assert!(parts.insert_item(Arc::new(Part { id: 2, uid: uid.clone() })).is_none());
+        parts.insert(3, factory);
+        assert!(parts.modify(3, Arc::new(Part { id: 3, uid: uid.clone() })).is_none());
+        let tags = session.get_table_mut(182).unwrap();
+        assert!(tags.insert_item(Arc::new(Tag { id: 1, uid: uid.clone() })).is_none());
+        session.commit_all().unwrap();
+
+        // 먼저 가진 아이템을 지우면 다른 테이블에서 쓸 수 있음
+        session.get_table_mut(181).unwrap().remove(1);
+        let tags = session.get_table_mut(182).unwrap();
+        assert!(tags.insert_item(Arc::new(Tag { id: 1, uid: uid.clone() })).is_some());
+        session.commit_all().unwrap();
+        let (table, cursor) = session.find_by_guid(&uid).unwrap();
+        assert_eq!((table.table_type, cursor.key()), (182, 1));
+        assert!(session.get_table(181).unwrap().find_by_guid(&uid).is_none());
+
+        // 다른 테이블을 비워도 색인 항목은 남음
+        session.get_table_mut(181).unwrap().clear();
+        assert_eq!(session.find_by_guid(&uid).map(|(t, _)| t.table_type), Some(182));
+    }
+
+    #[test]
+    fn test_find_by_guid_survives_undo_and_reload() {
+        let factory = item_factory_mut();
+        assert!(Body::register(factory));
+
+        let dir = get_db_temp_path();
+        std::fs::create_dir_all(&dir).unwrap();
+        let log_path = dir.join("guid_items.log");
+        let snapshot_path = dir.join("guid_items.snap");
+        let _ = std::fs::remove_file(&log_path);
+        let (log_path, snapshot_path) = (log_path.to_str().unwrap(), snapshot_path.to_str().unwrap());
+
+        let uid = Guid::new_v7();
+        {
+            let mut session = Session::open(log_path).unwrap();
+            session.register_table(180, 1800);
+            let table = session.get_table_mut(180).unwrap();
+            table.insert(1, factory);
+            table.insert(2, factory); // null GUID → 색인하지 않음
+            table.modify(1, Arc::new(Body { id: 1, uid: uid.clone() }));
+            session.commit_all().unwrap();
+            let (table, cursor) = session.find_by_guid(&uid).unwrap();
+            assert_eq!((table.table_type, cursor.key()), (180, 1));
+            assert_eq!(table.guid_count(), 1);
+
+            session.get_table_mut(180).unwrap().remove(1);
+            session.commit_all().unwrap();
+            assert!(session.find_by_guid(&uid).is_none());
+            session.undo_all().unwrap();
+            assert_eq!(session.find_by_guid(&uid).map(|(_, c)| c.key()), Some(1));
+            session.redo_all().unwrap();
+            session.undo_all().unwrap();
+        }
+
+        let session = Session::open(log_path).unwrap();
+        assert_eq!(session.find_by_guid(&uid).map(|(_, c)| c.key()), Some(1));
+        session.save_snapshot(snapshot_path).unwrap();
+        let loaded = Session::load_snapshot(snapshot_path).unwrap();
+        assert_eq!(loaded.find_by_guid(&uid).map(|(_, c)| c.key()), Some(1));
+        assert!(loaded.find_by_guid(&Guid::new()).is_none());
+    }
+}
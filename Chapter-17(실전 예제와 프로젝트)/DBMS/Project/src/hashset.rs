@@ -1,65 +1,254 @@
-use std::collections::HashMap;
-use std::sync::Arc;
+use std::collections::{BTreeMap, HashMap};
+use std::ops::{Bound, RangeBounds};
 
-use crate::item::{Cursor, DItem};
+use crate::item::Cursor;
+use crate::key::TableKey;
 
-pub struct HashSetTable {
+/// 키 저장 방식 (테이블마다 선택)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum StorageMode {
+    #[default]
+    Hash,    // 순서 없음, 조회가 가장 빠름
+    Ordered, // B-tree: 키 순 반복, 범위 조회
+}
+
+enum Storage<K> {
+    Hash(HashMap<K, Vec<Cursor>>),
+    Ordered(BTreeMap<K, Vec<Cursor>>),
+}
+
+/// 키셋 페이지: next 가 있으면 다음 페이지의 기준 키
+#[derive(Clone, Debug, Default)]
+pub struct KeyPage<K = i32> {
+    pub items: Vec<Cursor>,
+    pub next: Option<K>,
+}
+
+/// 키 타입 K 로 아이템을 저장 (키는 TableKey::of 로 아이템에서 꺼냄)
+///
+/// 저장소가 저장 방식별 내부 타입으로 바뀌어 예전의 `pub items: HashMap<i32, Vec<Cursor>>` 는 없어짐.
+/// `items.get(&key)` → find, `items.iter()` → entries, `items.iter_mut()` → entries_mut,
+/// `items.contains_key(&key)` → contains_key, `items.len()` → count 로 바꿔 사용
+pub struct HashSetTable<K: TableKey = i32> {
     pub table_type: u16,
     pub item_type: u16,
-    pub items: HashMap<i32, Vec<Cursor>>, // key → list of items
+    items: Storage<K>, // key → list of items
 }
 
 
-impl HashSetTable {
+impl<K: TableKey> HashSetTable<K> {
     pub fn new(table_type: u16, item_type: u16) -> Self {
+        Self::with_mode(table_type, item_type, StorageMode::Hash)
+    }
+
+    pub fn with_mode(table_type: u16, item_type: u16, mode: StorageMode) -> Self {
+        let items = match mode {
+            StorageMode::Hash => Storage::Hash(HashMap::new()),
+            StorageMode::Ordered => Storage::Ordered(BTreeMap::new()),
+        };
         HashSetTable {
             table_type,
             item_type,
-            items: HashMap::new(),
+            items,
         }
     }
 
+    pub fn mode(&self) -> StorageMode {
+        match self.items {
+            Storage::Hash(_) => StorageMode::Hash,
+            Storage::Ordered(_) => StorageMode::Ordered,
+        }
+    }
+
+    /// 저장 방식 변경 (기존 항목은 그대로 옮김)
+    pub fn set_mode(&mut self, mode: StorageMode) {
+        if self.mode() == mode {
+            return;
+        }
+        let old = std::mem::replace(&mut self.items, Storage::Hash(HashMap::new()));
+        self.items = match old {
+            Storage::Hash(map) => Storage::Ordered(map.into_iter().collect()),
+            Storage::Ordered(map) => Storage::Hash(map.into_iter().collect()),
+        };
+    }
+
+    /// 키 타입이 맞지 않는 아이템은 넣지 않음
     pub fn insert(&mut self, cursor: Cursor) {
-        let key = cursor.key();
-        self.items.entry(key).or_default().push(cursor);
+        let Some(key) = cursor.key_as::<K>() else {
+            return;
+        };
+        match &mut self.items {
+            Storage::Hash(map) => map.entry(key).or_default().push(cursor),
+            Storage::Ordered(map) => map.entry(key).or_default().push(cursor),
+        }
     }
 
-    pub fn remove(&mut self, key: i32) -> Option<Vec<Cursor>> {
-        if let Some(list) = self.items.remove(&key) {
-            Some(list)
-        } else {
-            None
+    /// 같은 키의 기존 항목을 새 커서 하나로 교체 (기존 목록 반환)
+    pub fn replace(&mut self, cursor: Cursor) -> Option<Vec<Cursor>> {
+        let key = cursor.key_as::<K>()?;
+        match &mut self.items {
+            Storage::Hash(map) => map.insert(key, vec![cursor]),
+            Storage::Ordered(map) => map.insert(key, vec![cursor]),
         }
     }
 
+    pub fn remove(&mut self, key: K) -> Option<Vec<Cursor>> {
+        match &mut self.items {
+            Storage::Hash(map) => map.remove(&key),
+            Storage::Ordered(map) => map.remove(&key),
+        }
+    }
 
-    pub fn find(&self, key: i32) -> Option<&Vec<Cursor>> {
-        self.items.get(&key)
+
+    pub fn find(&self, key: K) -> Option<&Vec<Cursor>> {
+        match &self.items {
+            Storage::Hash(map) => map.get(&key),
+            Storage::Ordered(map) => map.get(&key),
+        }
     }
 
-    pub fn find_mut(&mut self, key: i32) -> Option<&mut Vec<Cursor>> {
-        self.items.get_mut(&key)
+    pub fn find_mut(&mut self, key: K) -> Option<&mut Vec<Cursor>> {
+        match &mut self.items {
+            Storage::Hash(map) => map.get_mut(&key),
+            Storage::Ordered(map) => map.get_mut(&key),
+        }
     }
 
     pub fn clear(&mut self) {
-        self.items.clear();
+        match &mut self.items {
+            Storage::Hash(map) => map.clear(),
+            Storage::Ordered(map) => map.clear(),
+        }
     }
 
     pub fn count(&self) -> usize {
-        self.items.len()
+        match &self.items {
+            Storage::Hash(map) => map.len(),
+            Storage::Ordered(map) => map.len(),
+        }
+    }
+
+    /// 전체 항목 (Ordered 모드는 키 순)
+    pub fn all_items(&self) -> Box<dyn Iterator<Item = &Cursor> + '_> {
+        match &self.items {
+            Storage::Hash(map) => Box::new(map.values().flat_map(|v| v.iter())),
+            Storage::Ordered(map) => Box::new(map.values().flat_map(|v| v.iter())),
+        }
+    }
+
+    pub fn contains_key(&self, key: K) -> bool {
+        self.find(key).is_some()
+    }
+
+    /// (키, 항목 목록) 전체 (Ordered 모드는 키 순)
+    pub fn entries(&self) -> Box<dyn Iterator<Item = (&K, &Vec<Cursor>)> + '_> {
+        match &self.items {
+            Storage::Hash(map) => Box::new(map.iter()),
+            Storage::Ordered(map) => Box::new(map.iter()),
+        }
+    }
+
+    pub fn entries_mut(&mut self) -> Box<dyn Iterator<Item = (&K, &mut Vec<Cursor>)> + '_> {
+        match &mut self.items {
+            Storage::Hash(map) => Box::new(map.iter_mut()),
+            Storage::Ordered(map) => Box::new(map.iter_mut()),
+        }
     }
 
-    pub fn all_items(&self) -> impl Iterator<Item = &Cursor> {
-        self.items.values().flat_map(|v| v.iter())
+    /// 전체 키 (Ordered 모드는 오름차순)
+    pub fn keys(&self) -> Vec<K> {
+        match &self.items {
+            Storage::Hash(map) => map.keys().cloned().collect(),
+            Storage::Ordered(map) => map.keys().cloned().collect(),
+        }
     }
 }
 
-impl HashSetTable {
-    pub fn find_visible(&self, key: i32) -> Option<&Cursor> {
-        self.items.get(&key)?.iter().find(|c| c.visible)
+impl<K: TableKey> HashSetTable<K> {
+    pub fn find_visible(&self, key: K) -> Option<&Cursor> {
+        self.find(key)?.iter().find(|c| c.visible)
+    }
+
+    pub fn find_alive(&self, key: K) -> Option<&Cursor> {
+        self.find(key)?.iter().find(|c| c.is_alive())
     }
+}
 
-    pub fn find_alive(&self, key: i32) -> Option<&Cursor> {
-        self.items.get(&key)?.iter().find(|c| c.is_alive())
+/// 키 순 조회: Ordered 모드는 B-tree 를 그대로 따라가고, Hash 모드는 키를 정렬해서 처리
+impl<K: TableKey> HashSetTable<K> {
+    /// 범위 안의 보이는 항목 (키 오름차순)
+    pub fn range<R: RangeBounds<K>>(&self, range: R) -> Box<dyn Iterator<Item = &Cursor> + '_> {
+        self.scan(range, false)
     }
+
+    /// 범위 안의 보이는 항목 (키 내림차순)
+    pub fn range_rev<R: RangeBounds<K>>(&self, range: R) -> Box<dyn Iterator<Item = &Cursor> + '_> {
+        self.scan(range, true)
+    }
+
+    pub fn first(&self) -> Option<&Cursor> {
+        self.range(..).next()
+    }
+
+    pub fn last(&self) -> Option<&Cursor> {
+        self.range_rev(..).next()
+    }
+
+    /// 키셋 페이지: after 보다 큰 키부터 limit 개 (after = 이전 페이지의 next)
+    pub fn page(&self, after: Option<K>, limit: usize) -> KeyPage<K> {
+        let lower = after.map_or(Bound::Unbounded, Bound::Excluded);
+        Self::take_page(self.range((lower, Bound::Unbounded)), limit)
+    }
+
+    /// 역순 키셋 페이지: before 보다 작은 키부터 limit 개
+    pub fn page_rev(&self, before: Option<K>, limit: usize) -> KeyPage<K> {
+        let upper = before.map_or(Bound::Unbounded, Bound::Excluded);
+        Self::take_page(self.range_rev((Bound::Unbounded, upper)), limit)
+    }
+
+    fn take_page<'a>(mut iter: impl Iterator<Item = &'a Cursor>, limit: usize) -> KeyPage<K> {
+        let items: Vec<Cursor> = iter.by_ref().take(limit).cloned().collect();
+        let next = match (items.last(), iter.next()) {
+            (Some(last), Some(_)) => last.key_as::<K>(),
+            _ => None,
+        };
+        KeyPage { items, next }
+    }
+
+    fn scan<R: RangeBounds<K>>(&self, range: R, reverse: bool) -> Box<dyn Iterator<Item = &Cursor> + '_> {
+        if is_empty_range(&range) {
+            return Box::new(std::iter::empty());
+        }
+        match &self.items {
+            Storage::Ordered(map) => {
+                let entries = map.range(range);
+                if reverse {
+                    Box::new(entries.rev().filter_map(|(_, list)| first_visible(list)))
+                } else {
+                    Box::new(entries.filter_map(|(_, list)| first_visible(list)))
+                }
+            }
+            Storage::Hash(map) => {
+                let mut keys: Vec<&K> = map.keys().filter(|key| range.contains(*key)).collect();
+                keys.sort();
+                if reverse {
+                    keys.reverse();
+                }
+                Box::new(keys.into_iter().filter_map(move |key| first_visible(&map[key])))
+            }
+        }
+    }
+}
+
+/// 시작이 끝보다 크거나, 같은데 한쪽이 제외된 범위 (BTreeMap::range 는 이런 범위에서 panic)
+pub fn is_empty_range<T: Ord, R: RangeBounds<T>>(range: &R) -> bool {
+    match (range.start_bound(), range.end_bound()) {
+        (Bound::Included(start), Bound::Included(end)) => start > end,
+        (Bound::Included(start) | Bound::Excluded(start), Bound::Included(end) | Bound::Excluded(end)) => start >= end,
+        _ => false,
+    }
+}
+
+fn first_visible(list: &[Cursor]) -> Option<&Cursor> {
+    list.iter().find(|c| c.visible)
 }
\ No newline at end of file
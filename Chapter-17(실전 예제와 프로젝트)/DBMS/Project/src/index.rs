@@ -0,0 +1,117 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::ops::RangeBounds;
+use std::sync::Arc;
+
+use crate::hashset::is_empty_range;
+use crate::item::DItem;
+use crate::key::TableKey;
+
+/// 인덱스 값 (같음 비교와 순서 비교 모두 가능)
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum IndexValue {
+    Bool(bool),
+    Int(i64),
+    Str(String),
+}
+
+impl From<bool> for IndexValue {
+    fn from(v: bool) -> Self {
+        IndexValue::Bool(v)
+    }
+}
+
+impl From<i32> for IndexValue {
+    fn from(v: i32) -> Self {
+        IndexValue::Int(v as i64)
+    }
+}
+
+impl From<i64> for IndexValue {
+    fn from(v: i64) -> Self {
+        IndexValue::Int(v)
+    }
+}
+
+impl From<&str> for IndexValue {
+    fn from(v: &str) -> Self {
+        IndexValue::Str(v.to_string())
+    }
+}
+
+impl From<String> for IndexValue {
+    fn from(v: String) -> Self {
+        IndexValue::Str(v)
+    }
+}
+
+/// 아이템에서 인덱스 값을 뽑는 함수 (None 이면 인덱스에 넣지 않음)
+pub type IndexExtractor = Arc<dyn Fn(&dyn DItem) -> Option<IndexValue> + Send + Sync>;
+
+/// 보조 인덱스 하나: 값 → 키 목록
+pub struct SecondaryIndex<K = i32> {
+    pub name: String,
+    extractor: IndexExtractor,
+    entries: BTreeMap<IndexValue, BTreeSet<K>>,
+    values: HashMap<K, IndexValue>, // 키 → 현재 인덱스 값 (갱신 시 이전 값 제거용)
+}
+
+impl<K: TableKey> SecondaryIndex<K> {
+    pub fn new(name: &str, extractor: IndexExtractor) -> Self {
+        SecondaryIndex {
+            name: name.to_string(),
+            extractor,
+            entries: BTreeMap::new(),
+            values: HashMap::new(),
+        }
+    }
+
+    /// 키의 항목을 새 아이템 기준으로 갱신 (None = 삭제됨)
+    pub fn update(&mut self, key: K, item: Option<&dyn DItem>) {
+        let value = item.and_then(|item| (self.extractor)(item));
+        if self.values.get(&key) == value.as_ref() {
+            return;
+        }
+        if let Some(old) = self.values.remove(&key)
+            && let Some(keys) = self.entries.get_mut(&old)
+        {
+            keys.remove(&key);
+            if keys.is_empty() {
+                self.entries.remove(&old);
+            }
+        }
+        if let Some(value) = value {
+            self.entries.entry(value.clone()).or_default().insert(key.clone());
+            self.values.insert(key, value);
+        }
+    }
+
+    /// 값이 같은 키 (오름차순)
+    pub fn find(&self, value: &IndexValue) -> Vec<K> {
+        self.entries.get(value).map(|keys| keys.iter().cloned().collect()).unwrap_or_default()
+    }
+
+    /// 값이 범위에 드는 키 (값 순, 같은 값이면 키 순)
+    pub fn range<R: RangeBounds<IndexValue>>(&self, range: R) -> Vec<K> {
+        if is_empty_range(&range) {
+            return Vec::new();
+        }
+        self.entries.range(range).flat_map(|(_, keys)| keys.iter().cloned()).collect()
+    }
+
+    pub fn value_of(&self, key: K) -> Option<&IndexValue> {
+        self.values.get(&key)
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.values.clear();
+    }
+}
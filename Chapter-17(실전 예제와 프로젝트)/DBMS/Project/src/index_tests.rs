@@ -0,0 +1,80 @@
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use crate::index::IndexValue;
+    use crate::item::DItem;
+    use crate::item_factory::item_factory_mut;
+    use crate::session::Session;
+
+    #[derive(Debug)]
+    struct Part {
+        key: i32,
+        color: String,
+        weight: i64,
+    }
+
+    impl DItem for Part {
+        fn key(&self) -> i32 { self.key }
+        fn item_type(&self) -> u16 { 700 }
+        fn table_type(&self) -> u16 { 70 }
+        fn serialize(&self, _stream: &mut dyn crate::tx_stream::TxStream, _session: &Session) {}
+    }
+
+    fn part(key: i32, color: &str, weight: i64) -> Arc<dyn DItem> {
+        Arc::new(Part { key, color: color.to_string(), weight })
+    }
+
+    fn keys(cursors: Vec<&crate::item::Cursor>) -> Vec<i32> {
+        cursors.iter().map(|c| c.key()).collect()
+    }
+
+    #[test]
+    fn test_secondary_index_follows_edits_undo_redo() {
+        let factory = item_factory_mut();
+        factory.lock().unwrap().register_type(
+            700,
+            70,
+            Arc::new(|key| Arc::new(Part { key, color: String::new(), weight: 0 })),
+            Arc::new(|_item| {}),
+        );
+        let mut session = Session::new();
+        session.register_table(70, 700);
+
+        let table = session.get_table_mut(70).unwrap();
+        for key in 1..=3 {
+            table.insert(key, factory);
+        }
+        table.modify(1, part(1, "red", 10));
+        table.modify(2, part(2, "blue", 30));
+        table.modify(3, part(3, "red", 20));
+        session.commit_all().unwrap();
+
+        // 등록 시 기존 아이템으로 채움
+        let table = session.get_table_mut(70).unwrap();
+        assert!(table.create_index("color", Arc::new(|item| {
+            item.downcast_ref::<Part>().map(|p| IndexValue::from(p.color.as_str()))
+        })));
+        assert!(table.create_index("weight", Arc::new(|item| {
+            item.downcast_ref::<Part>().map(|p| IndexValue::from(p.weight))
+        })));
+        assert_eq!(keys(table.find_by_index("color", &"red".into())), vec![1, 3]);
+        assert_eq!(keys(table.range_by_index("weight", IndexValue::from(15)..)), vec![3, 2]);
+        assert!(table.range_by_index("weight", IndexValue::from(30)..IndexValue::from(10)).is_empty());
+
+        table.modify(3, part(3, "blue", 5));
+        table.remove(1);
+        assert!(table.find_by_index("color", &"red".into()).is_empty());
+        assert_eq!(keys(table.find_by_index("color", &"blue".into())), vec![2, 3]);
+        session.commit_all().unwrap();
+
+        session.undo_all().unwrap();
+        let table = session.get_table(70).unwrap();
+        assert_eq!(keys(table.find_by_index("color", &"red".into())), vec![1, 3]);
+        assert_eq!(table.index("weight").unwrap().value_of(3), Some(&IndexValue::Int(20)));
+
+        session.redo_all().unwrap();
+        let table = session.get_table(70).unwrap();
+        assert_eq!(keys(table.find_by_index("color", &"blue".into())), vec![2, 3]);
+        assert_eq!(table.index("color").unwrap().len(), 2);
+    }
+}
@@ -1,12 +1,40 @@
+use std::any::Any;
 use std::sync::Arc;
+use crate::guid::Guid;
+use crate::key::TableKey;
 use crate::session::Session;
 use crate::tx_stream::TxStream;
 
-pub trait DItem: std::fmt::Debug + Send + Sync {
+pub use dbms_derive::DItem;
+
+pub trait DItem: std::fmt::Debug + Send + Sync + Any {
+    /// i32 키 (table_key 가 있는 아이템은 0: 식별자로 쓰지 말고 Cursor::key_as 사용)
     fn key(&self) -> i32;
     fn item_type(&self) -> u16;
     fn table_type(&self) -> u16;
     fn serialize(&self, stream: &mut dyn TxStream, session: &Session);
+
+    /// serialize 가 쓰는 페이로드 형식 버전 (ItemFactory 에 등록한 버전과 같아야 함)
+    fn schema_version(&self) -> u16 {
+        1
+    }
+
+    /// 키와 별개로 유지되는 영구 식별자 (세션 전체에서 유일해야 함, None = 없음)
+    fn guid(&self) -> Option<Guid> {
+        None
+    }
+
+    /// i32 가 아닌 키 타입의 테이블에서 쓰는 키 (TableKey::of 가 꺼냄, None = i32 키만 있음)
+    fn table_key(&self) -> Option<&dyn Any> {
+        None
+    }
+}
+
+impl dyn DItem {
+    /// 실제 아이템 타입으로 변환 (인덱스 추출기 등에서 필드 접근용)
+    pub fn downcast_ref<T: DItem>(&self) -> Option<&T> {
+        (self as &dyn Any).downcast_ref::<T>()
+    }
 }
 
 
@@ -18,6 +46,8 @@ pub struct Cursor {
     pub temp_data: u16,
     pub param_data: u8,
     pub param: usize,
+    pub version: u64,  // 커밋 버전 (0 = 아직 커밋되지 않음)
+    pub revision: u64, // 삽입/수정 때 받은 번호 (낙관적 동시성 검사용, next_revision 참고)
 }
 
 impl Cursor {
@@ -28,6 +58,8 @@ impl Cursor {
             temp_data: 0,
             param_data: 0,
             param: 0,
+            version: 0,
+            revision: 0,
         }
     }
 
@@ -40,6 +72,11 @@ impl Cursor {
         self.data.key()
     }
 
+    /// 키 타입 K 의 키
+    pub fn key_as<K: TableKey>(&self) -> Option<K> {
+        K::of(self.data.as_ref())
+    }
+
     pub fn item_type(&self) -> u16 {
         self.data.item_type()
     }
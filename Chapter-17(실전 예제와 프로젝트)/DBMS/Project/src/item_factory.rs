@@ -1,21 +1,34 @@
-use std::collections::HashMap;
-use std::sync::{Arc, Mutex, OnceLock};
+use std::collections::{BTreeMap, HashMap};
+use std::io;
+use std::sync::{Arc, Mutex};
 use once_cell::sync::Lazy; // ✅ 반드시 sync 버전
-use crate::item::{DItem, Cursor};
+use crate::item::DItem;
+use crate::tx_stream::{MemTxStream, TxStream};
+use crate::reflect::{FieldDef, FieldValue, ItemSchema};
 
 pub type CreateCallback = Arc<dyn Fn(i32) -> Arc<dyn DItem> + Send + Sync>;
 
 pub type DestroyCallback = Arc<dyn Fn(Arc<dyn DItem>) + Send + Sync>;
 
+/// serialize 로 기록한 페이로드에서 아이템 복원 (key 는 액션/스냅샷에 기록된 키)
+pub type DeserializeCallback = Arc<dyn Fn(i32, &mut dyn TxStream) -> Option<Arc<dyn DItem>> + Send + Sync>;
+
+/// 스키마 버전 N 의 페이로드를 N+1 형식으로 변환
+pub type MigrationCallback = Arc<dyn Fn(&[u8]) -> Option<Vec<u8>> + Send + Sync>;
+
 #[derive(Clone)]
 pub struct TypeInfo {
-    pub create: CreateCallback,
+    pub create: Option<CreateCallback>, // None = i32 키로 만들 수 없는 타입 (Table::insert_item 으로 삽입)
     pub destroy: DestroyCallback,
     pub item_type: u16,
     pub table_type: u16,
+    pub deserialize: Option<DeserializeCallback>, // 없으면 create 로 빈 아이템 생성
+    pub schema: Option<ItemSchema>,               // 필드 리플렉션 (선택)
+    pub schema_version: u16,                      // 현재 페이로드 형식 버전 (1 부터)
+    pub migrations: BTreeMap<u16, MigrationCallback>, // from 버전 → 다음 버전 변환
 }
 
-#[derive(Clone)]
+#[derive(Clone, Default)]
 pub struct ItemFactory {
     registry: HashMap<u16, TypeInfo>, // key: item_type
 }
@@ -36,6 +49,15 @@ impl ItemFactory {
         create: CreateCallback,
         destroy: DestroyCallback,
     ) -> bool {
+        self.register(item_type, table_type, Some(create), destroy)
+    }
+
+    /// i32 가 아닌 키 타입의 아이템 등록: create_item 으로는 만들 수 없고 복원, 소멸만 지원
+    pub fn register_keyed_type(&mut self, item_type: u16, table_type: u16, destroy: DestroyCallback) -> bool {
+        self.register(item_type, table_type, None, destroy)
+    }
+
+    fn register(&mut self, item_type: u16, table_type: u16, create: Option<CreateCallback>, destroy: DestroyCallback) -> bool {
         if item_type == 0 || table_type == 0 || self.registry.contains_key(&item_type) {
             return false;
         }
@@ -47,13 +69,103 @@ impl ItemFactory {
                 destroy,
                 item_type,
                 table_type,
+                deserialize: None,
+                schema: None,
+                schema_version: 1,
+                migrations: BTreeMap::new(),
             },
         );
         true
     }
 
     pub fn create_item(&self, item_type: u16, key: i32) -> Option<Arc<dyn DItem>> {
-        self.registry.get(&item_type).map(|info| (info.create)(key))
+        let create = self.registry.get(&item_type)?.create.as_ref()?;
+        Some(create(key))
+    }
+
+    /// 등록된 타입에 페이로드 복원 함수 지정
+    pub fn register_deserializer(&mut self, item_type: u16, deserialize: DeserializeCallback) -> bool {
+        match self.registry.get_mut(&item_type) {
+            Some(info) => {
+                info.deserialize = Some(deserialize);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// 현재 스키마 버전 지정 (DItem::schema_version 과 같아야 함)
+    pub fn set_schema_version(&mut self, item_type: u16, version: u16) -> bool {
+        match self.registry.get_mut(&item_type) {
+            Some(info) if version > 0 => {
+                info.schema_version = version;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn schema_version(&self, item_type: u16) -> Option<u16> {
+        Some(self.registry.get(&item_type)?.schema_version)
+    }
+
+    /// from_version → from_version + 1 변환 함수 등록
+    pub fn register_migration(&mut self, item_type: u16, from_version: u16, migrate: MigrationCallback) -> bool {
+        match self.registry.get_mut(&item_type) {
+            Some(info) if from_version > 0 => {
+                info.migrations.insert(from_version, migrate);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// 기록된 버전의 페이로드를 현재 버전까지 차례로 변환
+    pub fn migrate_payload(&self, item_type: u16, version: u16, payload: Vec<u8>) -> io::Result<Vec<u8>> {
+        let info = self.registry.get(&item_type).ok_or_else(|| not_registered(item_type))?;
+        if version == 0 || version > info.schema_version {
+            return Err(invalid(format!(
+                "item_type {} payload has schema version {}, but the registered version is {}",
+                item_type, version, info.schema_version
+            )));
+        }
+        let mut payload = payload;
+        for from in version..info.schema_version {
+            let migrate = info.migrations.get(&from).ok_or_else(|| {
+                invalid(format!("item_type {} has no migration from schema version {}", item_type, from))
+            })?;
+            payload = migrate(&payload).ok_or_else(|| {
+                invalid(format!("item_type {} migration from schema version {} failed", item_type, from))
+            })?;
+        }
+        Ok(payload)
+    }
+
+    /// 페이로드로 아이템 복원: 현재 버전으로 변환한 뒤 복원 함수 호출
+    /// (복원 함수가 없으면 기록된 내용을 잃지 않도록 실패)
+    pub fn deserialize_item(&self, item_type: u16, key: i32, version: u16, payload: Vec<u8>) -> io::Result<Arc<dyn DItem>> {
+        let info = self.registry.get(&item_type).ok_or_else(|| not_registered(item_type))?;
+        let deserialize = info
+            .deserialize
+            .as_ref()
+            .ok_or_else(|| invalid(format!("item_type {} has no deserializer", item_type)))?;
+        let payload = self.migrate_payload(item_type, version, payload)?;
+        let mut stream = MemTxStream::from_bytes(payload);
+        match deserialize(key, &mut stream) {
+            Some(item) if item.key() == key && item.item_type() == item_type => Ok(item),
+            _ => Err(invalid(format!("item_type {} key {} has an invalid payload", item_type, key))),
+        }
+    }
+
+    /// 로그나 스냅샷에 기록해도 다시 읽을 수 있는 타입인지 (복원 함수가 있어야 함)
+    pub fn check_restorable(&self, item_type: u16) -> io::Result<()> {
+        match self.registry.get(&item_type) {
+            Some(info) if info.deserialize.is_some() => Ok(()),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("item_type {} has no deserializer and cannot be written to a log or snapshot", item_type),
+            )),
+        }
     }
 
     pub fn destroy_item(&self, item: Arc<dyn DItem>) {
@@ -66,6 +178,61 @@ impl ItemFactory {
     pub fn get_type_info(&self, item_type: u16) -> Option<&TypeInfo> {
         self.registry.get(&item_type)
     }
+
+    /// 등록된 타입에 필드 스키마 지정
+    pub fn register_schema(&mut self, item_type: u16, schema: ItemSchema) -> bool {
+        match self.registry.get_mut(&item_type) {
+            Some(info) => {
+                info.schema = Some(schema);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn get_schema(&self, item_type: u16) -> Option<&ItemSchema> {
+        self.registry.get(&item_type)?.schema.as_ref()
+    }
+
+    pub fn field_def(&self, item_type: u16, name: &str) -> Option<&FieldDef> {
+        self.get_schema(item_type)?.find(name)
+    }
+
+    /// 이름으로 필드 읽기
+    pub fn get_field(&self, item: &dyn DItem, name: &str) -> Option<FieldValue> {
+        (self.field_def(item.item_type(), name)?.get)(item)
+    }
+
+    /// 이름으로 필드 쓰기: 값을 바꾼 새 아이템 (읽기 전용이거나 자료형이 다르면 None)
+    pub fn set_field(&self, item: &dyn DItem, name: &str, value: FieldValue) -> Option<Arc<dyn DItem>> {
+        let def = self.field_def(item.item_type(), name)?;
+        if value.field_type() != def.field_type {
+            return None;
+        }
+        let new_item = (def.set.as_ref()?)(item, value)?;
+        (new_item.key() == item.key() && new_item.item_type() == item.item_type()).then_some(new_item)
+    }
+
+    /// 스키마 순서대로 모든 필드 값 (내보내기, 디버깅용)
+    pub fn fields_of(&self, item: &dyn DItem) -> Vec<(String, FieldValue)> {
+        let Some(schema) = self.get_schema(item.item_type()) else {
+            return Vec::new();
+        };
+        schema
+            .fields
+            .iter()
+            .filter_map(|def| (def.get)(item).map(|value| (def.name.clone(), value)))
+            .collect()
+    }
+}
+
+
+fn not_registered(item_type: u16) -> io::Error {
+    invalid(format!("item_type {} is not registered in ItemFactory", item_type))
+}
+
+fn invalid(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
 }
 
 
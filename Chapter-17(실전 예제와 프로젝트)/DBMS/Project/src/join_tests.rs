@@ -0,0 +1,122 @@
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use crate::index::IndexValue;
+    use crate::item::DItem;
+    use crate::item_factory::item_factory_mut;
+    use crate::query::{Field, JoinKey, JoinPlan};
+    use crate::session::Session;
+
+    #[derive(Debug)]
+    struct Edge {
+        key: i32,
+    }
+
+    impl DItem for Edge {
+        fn key(&self) -> i32 { self.key }
+        fn item_type(&self) -> u16 { 1200 }
+        fn table_type(&self) -> u16 { 120 }
+        fn serialize(&self, _stream: &mut dyn crate::tx_stream::TxStream, _session: &Session) {}
+    }
+
+    #[derive(Debug)]
+    struct Face {
+        key: i32,
+        edge: i32,
+    }
+
+    impl DItem for Face {
+        fn key(&self) -> i32 { self.key }
+        fn item_type(&self) -> u16 { 1300 }
+        fn table_type(&self) -> u16 { 130 }
+        fn serialize(&self, _stream: &mut dyn crate::tx_stream::TxStream, _session: &Session) {}
+    }
+
+    fn edge_ref() -> Field {
+        Field::new("edge", |item| item.downcast_ref::<Face>().map(|f| IndexValue::from(f.edge)))
+    }
+
+    fn new_session() -> Session {
+        let factory = item_factory_mut();
+        {
+            let mut f = factory.lock().unwrap();
+            f.register_type(1200, 120, Arc::new(|key| Arc::new(Edge { key })), Arc::new(|_item| {}));
+            f.register_type(1300, 130, Arc::new(|key| Arc::new(Face { key, edge: 0 })), Arc::new(|_item| {}));
+        }
+        let mut session = Session::new();
+        session.register_table(120, 1200);
+        session.register_table(130, 1300);
+        let edges = session.get_table_mut(120).unwrap();
+        for key in 1..=3 {
+            edges.insert(key, factory);
+        }
+        // 면 → 변 참조 (변 9 는 없음)
+        let faces = session.get_table_mut(130).unwrap();
+        for (key, edge) in [(10, 1), (11, 1), (12, 2), (13, 9)] {
+            faces.insert(key, factory);
+            faces.modify(key, Arc::new(Face { key, edge }));
+        }
+        session.commit_all().unwrap();
+        session
+    }
+
+    fn pairs(rows: crate::query::JoinRows<'_>) -> Vec<(i32, i32)> {
+        rows.map(|(l, r)| (l.key(), r.key())).collect()
+    }
+
+    #[test]
+    fn test_join_faces_to_edges() {
+        let session = new_session();
+        let expected = vec![(10, 1), (11, 1), (12, 2)];
+
+        let join = session.query(130).join(120, JoinKey::Field(edge_ref()), JoinKey::Key);
+        assert_eq!(join.plan(), JoinPlan::KeyLookup);
+        assert_eq!(pairs(join.rows()), expected);
+        assert_eq!(pairs(join.hash_join()), expected);
+
+        // 지연 실행: 필요한 만큼만 만듦
+        assert_eq!(pairs(Box::new(join.rows().take(1))), vec![(10, 1)]);
+    }
+
+    #[test]
+    fn test_join_edges_to_faces_uses_index() {
+        let mut session = new_session();
+        let expected = vec![(1, 10), (1, 11), (2, 12)];
+
+        let scan = session.query(120).join(130, JoinKey::Key, JoinKey::Field(edge_ref()));
+        assert_eq!(scan.plan(), JoinPlan::Hash);
+        assert_eq!(pairs(scan.rows()), expected);
+        assert_eq!(pairs(scan.index_join()), expected);
+        drop(scan);
+
+        let field = edge_ref();
+        session.get_table_mut(130).unwrap().create_index(&field.name, field.extractor());
+        let indexed = session.query(120).key_range(2..).join(130, JoinKey::Key, JoinKey::Field(edge_ref()));
+        assert_eq!(indexed.plan(), JoinPlan::IndexLookup("edge".to_string()));
+        assert_eq!(pairs(indexed.rows()), vec![(2, 12)]);
+    }
+
+    #[test]
+    fn test_join_reads_left_side_lazily() {
+        let session = new_session();
+        let seen = std::cell::Cell::new(0);
+        let counted = || {
+            session.query(130).filter(|_| {
+                seen.set(seen.get() + 1);
+                true
+            })
+        };
+
+        // 첫 쌍을 만들 때까지 왼쪽은 한 행만 읽음
+        let join = counted().join(120, JoinKey::Field(edge_ref()), JoinKey::Key);
+        assert_eq!(pairs(Box::new(join.hash_join().take(1))), vec![(10, 1)]);
+        assert_eq!(seen.replace(0), 1);
+        assert_eq!(pairs(Box::new(join.index_join().take(1))), vec![(10, 1)]);
+        assert_eq!(seen.replace(0), 1);
+
+        // 키 역순도 차례로 읽음
+        let join = counted().order_by_key(true).join(120, JoinKey::Field(edge_ref()), JoinKey::Key);
+        assert_eq!(pairs(join.rows()), vec![(12, 2), (11, 1), (10, 1)]);
+        assert_eq!(seen.get(), 4);
+    }
+}
@@ -0,0 +1,92 @@
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::io;
+use std::sync::Mutex;
+
+use crate::define::TxAction;
+use crate::guid::Guid;
+use crate::index::IndexValue;
+use crate::item::DItem;
+use crate::item_factory::ItemFactory;
+use crate::session::Session;
+use crate::tx_stream::{StreamValue, TxStream};
+
+/// 테이블 키 타입: i32, i64, String, Guid 와 이들의 튜플
+/// 아이템은 DItem::key (i32) 외의 키를 DItem::table_key 로 알려줌
+///
+/// Table<K>, HashSetTable<K>, undo/redo 와 write_keyed_action/read_keyed_action 은 모든 키 타입을
+/// 다루지만, Session (WAL, 스냅샷, 참조, 잠금 포함)은 i32 키 테이블만 관리함
+/// (다른 키 타입 테이블 사이의 참조는 Reference::referrers/dangling 으로 직접 검사)
+pub trait TableKey: Clone + Eq + Hash + Ord + Debug + Send + Sync + StreamValue + 'static {
+    /// 아이템의 키 (키 타입이 맞지 않으면 None)
+    fn of(item: &dyn DItem) -> Option<Self> {
+        item.table_key()?.downcast_ref::<Self>().cloned()
+    }
+
+    /// 보조 인덱스 값으로 쓸 수 있는 키 (참조하는 아이템을 인덱스로 찾을 때 사용)
+    fn index_value(&self) -> Option<IndexValue> {
+        None
+    }
+}
+
+impl TableKey for i32 {
+    /// table_key 가 있는 아이템은 i32 키 테이블에 넣지 않음
+    fn of(item: &dyn DItem) -> Option<Self> {
+        item.table_key().is_none().then(|| item.key())
+    }
+
+    fn index_value(&self) -> Option<IndexValue> {
+        Some(IndexValue::from(*self))
+    }
+}
+
+impl TableKey for i64 {
+    fn index_value(&self) -> Option<IndexValue> {
+        Some(IndexValue::from(*self))
+    }
+}
+
+impl TableKey for String {
+    fn index_value(&self) -> Option<IndexValue> {
+        Some(IndexValue::from(self.clone()))
+    }
+}
+
+impl TableKey for Guid {}
+
+impl<A: TableKey, B: TableKey> TableKey for (A, B) {}
+
+impl<A: TableKey, B: TableKey, C: TableKey> TableKey for (A, B, C) {}
+
+/// 키 타입 K 의 키를 앞에 붙여 액션 기록 (키가 없는 액션은 기록하지 않음)
+/// 액션 안의 i32 키 자리는 DItem::key 그대로이고, 아이템은 앞에 붙인 키로만 구분함
+pub fn write_keyed_action<K: TableKey>(stream: &mut dyn TxStream, action: &TxAction, session: &Session) -> bool {
+    let Some(key) = action.key_as::<K>() else {
+        return false;
+    };
+    key.write_to(stream);
+    stream.write_action(action, session);
+    true
+}
+
+/// write_keyed_action 으로 기록한 액션 읽기: Ok(None) = 스트림이 중간에 끊김
+/// 앞에 붙은 키와 복원한 아이템의 키가 다르면 InvalidData
+pub fn read_keyed_action<K: TableKey>(
+    stream: &mut dyn TxStream,
+    item_type: u16,
+    factory: &Mutex<ItemFactory>,
+) -> io::Result<Option<(K, TxAction)>> {
+    let Some(key) = K::read_from(stream) else {
+        return Ok(None);
+    };
+    let Some(action) = stream.try_read_action(item_type, factory)? else {
+        return Ok(None);
+    };
+    if action.key_as::<K>().as_ref() != Some(&key) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("keyed action for {:?} restored an item with a different key", key),
+        ));
+    }
+    Ok(Some((key, action)))
+}
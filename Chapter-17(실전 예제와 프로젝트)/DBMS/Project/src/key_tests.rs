@@ -0,0 +1,98 @@
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use crate::define::TxAction;
+    use crate::hashset::StorageMode;
+    use crate::item::{Cursor, DItem};
+    use crate::item_factory::item_factory_mut;
+    use crate::key::{read_keyed_action, write_keyed_action};
+    use crate::session::Session;
+    use crate::table::Table;
+    use crate::tx_delta_list::TxDeltaList;
+    use crate::tx_stream::MemTxStream;
+
+    #[derive(Debug, Clone, PartialEq, DItem)]
+    #[ditem(item_type = 1900, table_type = 190)]
+    struct Param {
+        #[key]
+        name: String,
+        value: f64,
+    }
+
+    #[derive(Debug, Clone, PartialEq, DItem)]
+    #[ditem(item_type = 1901, table_type = 191)]
+    struct Cell {
+        #[key]
+        pos: (i64, i64),
+        text: String,
+    }
+
+    fn param(name: &str, value: f64) -> Arc<dyn DItem> {
+        Arc::new(Param { name: name.to_string(), value })
+    }
+
+    #[test]
+    fn test_string_and_tuple_keyed_tables() {
+        let mut table: Table<String> = Table::with_storage(190, 1900, StorageMode::Ordered);
+        assert!(table.insert_item(param("width", 1.0)).is_some());
+        assert!(table.insert_item(param("height", 2.0)).is_some());
+        assert!(table.insert_item(param("width", 3.0)).is_none()); // 중복 키
+        table.commit();
+
+        assert!(table.modify("width".to_string(), param("width", 5.0)).is_some());
+        assert!(table.modify("width".to_string(), param("depth", 5.0)).is_none()); // 다른 키
+        table.remove("height".to_string());
+        let keys: Vec<String> = table.items.range(..).filter_map(|c| c.key_as()).collect();
+        assert_eq!(keys, ["width"]);
+        table.commit();
+
+        table.undo();
+        let width = table.get("width".to_string()).unwrap();
+        assert_eq!(width.data.downcast_ref::<Param>().unwrap().value, 1.0);
+        assert!(table.get("height".to_string()).is_some());
+
+        // 복합 키: 키 순 범위 조회와 페이지
+        let mut cells: Table<(i64, i64)> = Table::with_storage(191, 1901, StorageMode::Ordered);
+        for (row, col) in [(1, 2), (0, 5), (1, 0), (2, 1)] {
+            cells.insert_item(Arc::new(Cell { pos: (row, col), text: String::new() }));
+        }
+        let row1: Vec<(i64, i64)> = cells.items.range((1, i64::MIN)..(2, i64::MIN)).filter_map(|c| c.key_as()).collect();
+        assert_eq!(row1, [(1, 0), (1, 2)]);
+        let page = cells.items.page(None, 2);
+        assert_eq!(page.next, Some((1, 0)));
+    }
+
+    #[test]
+    fn test_delta_and_stream_use_table_keys() {
+        // 같은 String 키의 삽입 후 수정은 하나의 삽입으로 병합
+        let mut delta: TxDeltaList<String> = TxDeltaList::new();
+        let before = Cursor::new(param("speed", 1.0));
+        delta.add(TxAction::Insert(before.clone()));
+        delta.add(TxAction::Modify { before, after: Cursor::new(param("speed", 2.0)) });
+        delta.add(TxAction::Insert(Cursor::new(param("torque", 3.0))));
+        assert_eq!(delta.count(), 2);
+        assert!(matches!(&delta.actions[0], TxAction::Insert(c) if c.data.downcast_ref::<Param>().unwrap().value == 2.0));
+        assert!(delta.find_by_key("torque".to_string()).is_some());
+
+        // 키를 앞에 붙여 기록하고 같은 키로 복원
+        assert!(Param::register(item_factory_mut()));
+        // i32 키로는 만들 수 없음
+        assert!(item_factory_mut().lock().unwrap().create_item(1900, 1).is_none());
+        let mut int_table = Table::new(190, 1900);
+        assert!(int_table.insert(1, item_factory_mut()).is_none());
+        assert!(int_table.insert_item(param("speed", 1.0)).is_none());
+        assert!(int_table.get(0).is_none());
+        let session = Session::new();
+        let mut stream = MemTxStream::new();
+        for action in delta.iter() {
+            assert!(write_keyed_action::<String>(&mut stream, action, &session));
+        }
+        let mut stream = MemTxStream::from_bytes(stream.into_bytes());
+        let (key, action) = read_keyed_action::<String>(&mut stream, 1900, item_factory_mut()).unwrap().unwrap();
+        assert_eq!(key, "speed");
+        assert_eq!(action.key_as::<String>().as_deref(), Some("speed"));
+        let (key, _) = read_keyed_action::<String>(&mut stream, 1900, item_factory_mut()).unwrap().unwrap();
+        assert_eq!(key, "torque");
+        assert!(read_keyed_action::<String>(&mut stream, 1900, item_factory_mut()).unwrap().is_none());
+    }
+}
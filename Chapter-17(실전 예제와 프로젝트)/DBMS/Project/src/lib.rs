@@ -0,0 +1,45 @@
+// #[derive(DItem)] 이 만드는 ::dbms 경로를 크레이트 내부에서도 사용
+extern crate self as dbms;
+
+pub mod guid;
+pub mod dbutil;
+pub mod mem_pool;
+pub mod item;
+pub mod item_factory;
+pub mod hashset;
+pub mod tx_delta_list;
+pub mod tx_stream;
+pub mod tx_manager;
+pub mod table;
+pub mod session;
+pub mod transaction;
+pub mod define;
+pub mod wal;
+pub mod snapshot;
+pub mod observer;
+pub mod lock_manager;
+pub mod shared_session;
+pub mod mvcc;
+pub mod index;
+pub mod query;
+pub mod sql;
+pub mod reflect;
+pub mod reference;
+pub mod key;
+mod undo_redo_tests;
+mod wal_tests;
+mod snapshot_tests;
+mod transaction_tests;
+mod shared_session_tests;
+mod mvcc_tests;
+mod index_tests;
+mod storage_tests;
+mod query_tests;
+mod sql_tests;
+mod join_tests;
+mod reflect_tests;
+mod derive_tests;
+mod migration_tests;
+mod reference_tests;
+mod guid_tests;
+mod key_tests;
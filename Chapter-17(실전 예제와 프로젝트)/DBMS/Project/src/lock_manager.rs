@@ -0,0 +1,181 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::io;
+use std::sync::{Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+/// 잠금 단위: (table_type, key)
+pub type LockKey = (u16, i32);
+
+/// 잠금 종류
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LockMode {
+    Shared,    // 읽기: 여러 트랜잭션이 함께 보유
+    Exclusive, // 쓰기: 한 트랜잭션만 보유
+}
+
+/// 잠금 실패
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LockError {
+    Timeout { table_type: u16, key: i32 },
+    Deadlock { table_type: u16, key: i32 },
+}
+
+impl fmt::Display for LockError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LockError::Timeout { table_type, key } => {
+                write!(f, "lock wait timed out on table {} key {}", table_type, key)
+            }
+            LockError::Deadlock { table_type, key } => {
+                write!(f, "deadlock detected on table {} key {}", table_type, key)
+            }
+        }
+    }
+}
+
+impl std::error::Error for LockError {}
+
+impl From<LockError> for io::Error {
+    fn from(e: LockError) -> Self {
+        let kind = match e {
+            LockError::Timeout { .. } => io::ErrorKind::TimedOut,
+            LockError::Deadlock { .. } => io::ErrorKind::Deadlock, // 재시도해도 풀리지 않음: 트랜잭션을 되돌려야 함
+        };
+        io::Error::new(kind, e)
+    }
+}
+
+#[derive(Default)]
+struct KeyLock {
+    shared: HashSet<u64>,
+    exclusive: Option<u64>,
+}
+
+impl KeyLock {
+    /// tx 가 mode 로 잠그려 할 때 막고 있는 트랜잭션들
+    fn blockers(&self, tx: u64, mode: LockMode) -> Vec<u64> {
+        let mut blockers: Vec<u64> = self.exclusive.iter().copied().filter(|&t| t != tx).collect();
+        if mode == LockMode::Exclusive {
+            blockers.extend(self.shared.iter().copied().filter(|&t| t != tx));
+        }
+        blockers
+    }
+
+    fn is_free(&self) -> bool {
+        self.shared.is_empty() && self.exclusive.is_none()
+    }
+}
+
+#[derive(Default)]
+struct LockState {
+    locks: HashMap<LockKey, KeyLock>,
+    waiting: HashMap<u64, (LockKey, LockMode)>, // 대기 중인 트랜잭션 → 요청
+}
+
+impl LockState {
+    fn try_grant(&mut self, tx: u64, key: LockKey, mode: LockMode) -> bool {
+        let lock = self.locks.entry(key).or_default();
+        if !lock.blockers(tx, mode).is_empty() {
+            return false;
+        }
+        match mode {
+            LockMode::Shared => {
+                if lock.exclusive != Some(tx) {
+                    lock.shared.insert(tx);
+                }
+            }
+            LockMode::Exclusive => {
+                lock.shared.remove(&tx); // 업그레이드
+                lock.exclusive = Some(tx);
+            }
+        }
+        true
+    }
+
+    /// 대기 그래프(wait-for)에서 tx 로 돌아오는 경로가 있으면 교착 상태
+    fn would_deadlock(&self, tx: u64, key: LockKey, mode: LockMode) -> bool {
+        let mut stack = match self.locks.get(&key) {
+            Some(lock) => lock.blockers(tx, mode),
+            None => return false,
+        };
+        let mut visited = HashSet::new();
+        while let Some(other) = stack.pop() {
+            if other == tx {
+                return true;
+            }
+            if !visited.insert(other) {
+                continue;
+            }
+            if let Some((wait_key, wait_mode)) = self.waiting.get(&other)
+                && let Some(lock) = self.locks.get(wait_key)
+            {
+                stack.extend(lock.blockers(other, *wait_mode));
+            }
+        }
+        false
+    }
+}
+
+/// (table_type, key) 단위의 공유/배타 잠금 관리자
+#[derive(Default)]
+pub struct LockManager {
+    state: Mutex<LockState>,
+    released: Condvar,
+}
+
+impl LockManager {
+    pub fn new() -> Self {
+        LockManager::default()
+    }
+
+    /// 잠금 획득: 이미 보유한 잠금이면 바로 성공, 교착 상태나 timeout 이면 실패
+    pub fn acquire(&self, tx: u64, key: LockKey, mode: LockMode, timeout: Duration) -> Result<(), LockError> {
+        let deadline = Instant::now() + timeout;
+        let mut state = self.state.lock().unwrap();
+        loop {
+            if state.try_grant(tx, key, mode) {
+                state.waiting.remove(&tx);
+                return Ok(());
+            }
+            if state.would_deadlock(tx, key, mode) {
+                state.waiting.remove(&tx);
+                return Err(LockError::Deadlock { table_type: key.0, key: key.1 });
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                state.waiting.remove(&tx);
+                return Err(LockError::Timeout { table_type: key.0, key: key.1 });
+            }
+            state.waiting.insert(tx, (key, mode));
+            state = self.released.wait_timeout(state, deadline - now).unwrap().0;
+        }
+    }
+
+    /// tx 가 보유한 모든 잠금 해제
+    pub fn release_all(&self, tx: u64) {
+        let mut state = self.state.lock().unwrap();
+        state.waiting.remove(&tx);
+        state.locks.retain(|_, lock| {
+            lock.shared.remove(&tx);
+            if lock.exclusive == Some(tx) {
+                lock.exclusive = None;
+            }
+            !lock.is_free()
+        });
+        self.released.notify_all();
+    }
+
+    /// tx 가 보유한 잠금 종류 (없으면 None)
+    pub fn held_mode(&self, tx: u64, key: LockKey) -> Option<LockMode> {
+        let state = self.state.lock().unwrap();
+        let lock = state.locks.get(&key)?;
+        if lock.exclusive == Some(tx) {
+            Some(LockMode::Exclusive)
+        } else if lock.shared.contains(&tx) {
+            Some(LockMode::Shared)
+        } else {
+            None
+        }
+    }
+}
@@ -1,29 +1,15 @@
 use std::ptr::NonNull;
 use std::sync::Arc;
-use crate::dbutil::{format_string_args, get_db_temp_path, replace_all};
-use crate::guid::Guid;
-use crate::hashset::HashSetTable;
-use crate::item::{Cursor, DItem};
-use crate::item_factory::item_factory_mut;
-use crate::mem_pool::MemPool;
-use crate::session::Session;
-use crate::transaction::Transaction;
-
-mod guid;
-mod dbutil;
-mod mem_pool;
-mod item;
-mod item_factory;
-mod hashset;
-mod tx_delta_list;
-mod tx_stream;
-mod tx_manager;
-mod table;
-mod session;
-mod transaction;
-mod define;
-mod undo_redo;
-
+use dbms::dbutil::{get_db_temp_path, replace_all};
+use dbms::guid::Guid;
+use dbms::hashset::HashSetTable;
+use dbms::item::{Cursor, DItem};
+use dbms::item_factory::item_factory_mut;
+use dbms::mem_pool::MemPool;
+use dbms::session::Session;
+use dbms::transaction::Transaction;
+
+#[allow(dead_code)]
 #[derive(Debug)]
 struct MyNode {
     value: i32,
@@ -50,7 +36,7 @@ impl DItem for MyItem {
         self.table_type
     }
 
-    fn serialize(&self, _stream: &mut dyn crate::tx_stream::TxStream, _session: &crate::session::Session) {
+    fn serialize(&self, _stream: &mut dyn dbms::tx_stream::TxStream, _session: &dbms::session::Session) {
         // 직렬화 로직은 필요 시 구현
     }
 }
@@ -60,7 +46,7 @@ fn main() {
     let factory = item_factory_mut();
     {
         let guid = Guid::new();
-        println!("GUID: {}", guid.to_string());
+        println!("GUID: {}", guid);
 
         let parsed = Guid::from_string(&guid.to_string()).unwrap();
         assert_eq!(guid, parsed);
@@ -125,8 +111,8 @@ fn main() {
         table.insert(42, factory);
         table.remove(42);
 
-        session.undo_all(); // 삭제 취소
-        session.redo_all(); // 다시 삭제
+        session.undo_all().unwrap(); // 삭제 취소
+        session.redo_all().unwrap(); // 다시 삭제
 
     }
 
@@ -140,16 +126,16 @@ fn main() {
 
             let table = session.get_table_mut(10).unwrap();
             table.insert(42, factory);
-            let mut tx = Transaction::new(&mut session);
-            tx.commit(); // 명시적 커밋
+            let tx = Transaction::new(&mut session);
+            tx.commit().unwrap(); // 명시적 커밋
         }
 
         {
 
             let table = session.get_table_mut(10).unwrap();
             table.remove(42);
-            let mut tx = Transaction::new(&mut session);
-            tx.commit();
+            let tx = Transaction::new(&mut session);
+            tx.commit().unwrap();
             // rollback 생략 → Drop에서 자동 undo
         }
 
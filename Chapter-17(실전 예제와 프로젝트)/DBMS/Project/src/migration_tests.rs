@@ -0,0 +1,79 @@
+#[cfg(test)]
+mod tests {
+    use std::io;
+    use std::sync::Arc;
+    use crate::dbutil::get_db_temp_path;
+    use crate::item::DItem;
+    use crate::item_factory::item_factory_mut;
+    use crate::session::Session;
+    use crate::tx_stream::{MemTxStream, StreamValue};
+
+    // 스키마 버전 1 형식으로 기록하기 위한 이전 구조체
+    #[derive(Debug, DItem)]
+    #[ditem(item_type = 1600, table_type = 160)]
+    struct LabelV1 {
+        #[key]
+        id: i32,
+        text: String,
+    }
+
+    #[derive(Debug, PartialEq, DItem)]
+    #[ditem(item_type = 1600, table_type = 160, version = 2)]
+    struct Label {
+        #[key]
+        id: i32,
+        text: String,
+        color: u32,
+    }
+
+    /// 1 → 2: 색상 필드 추가 (기본 흰색)
+    fn add_color(payload: &[u8]) -> Option<Vec<u8>> {
+        let mut old = MemTxStream::from_bytes(payload.to_vec());
+        let id = i32::read_from(&mut old)?;
+        let text = String::read_from(&mut old)?;
+        let mut new = MemTxStream::new();
+        id.write_to(&mut new);
+        text.write_to(&mut new);
+        0xFFFFFFu32.write_to(&mut new);
+        Some(new.into_bytes())
+    }
+
+    #[test]
+    fn test_old_payloads_are_migrated_on_read() {
+        let factory = item_factory_mut();
+        assert!(Label::register(factory));
+        assert_eq!(factory.lock().unwrap().schema_version(1600), Some(2));
+
+        let dir = get_db_temp_path();
+        std::fs::create_dir_all(&dir).unwrap();
+        let log_path = dir.join("migration.log");
+        let snapshot_path = dir.join("migration.snap");
+        let _ = std::fs::remove_file(&log_path);
+        let (log_path, snapshot_path) = (log_path.to_str().unwrap(), snapshot_path.to_str().unwrap());
+
+        {
+            let mut session = Session::open(log_path).unwrap();
+            session.register_table(160, 1600);
+            let table = session.get_table_mut(160).unwrap();
+            table.insert(1, factory);
+            table.modify(1, Arc::new(LabelV1 { id: 1, text: "old".into() }));
+            session.commit_all().unwrap();
+            session.save_snapshot(snapshot_path).unwrap();
+        }
+
+        // 변환 함수가 없으면 잘린 로그로 취급하지 않고 오류
+        let err = Session::open(log_path).err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(Session::load_snapshot(snapshot_path).is_err());
+
+        assert!(factory.lock().unwrap().register_migration(1600, 1, Arc::new(add_color)));
+        let expected = Label { id: 1, text: "old".into(), color: 0xFFFFFF };
+        for session in [Session::open(log_path).unwrap(), Session::load_snapshot(snapshot_path).unwrap()] {
+            let cursor = session.get_table(160).unwrap().get(1).unwrap();
+            assert_eq!(cursor.data.downcast_ref::<Label>(), Some(&expected));
+        }
+
+        // 등록된 버전보다 새로운 페이로드는 읽지 않음
+        assert!(factory.lock().unwrap().deserialize_item(1600, 1, 3, Vec::new()).is_err());
+    }
+}
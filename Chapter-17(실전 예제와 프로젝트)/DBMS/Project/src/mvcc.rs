@@ -0,0 +1,217 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+
+use crate::item::Cursor;
+use crate::key::TableKey;
+
+static NEXT_VERSION: AtomicU64 = AtomicU64::new(1);
+
+/// 새 커밋 버전 (프로세스 전체에서 증가)
+pub fn next_version() -> u64 {
+    NEXT_VERSION.fetch_add(1, Ordering::SeqCst)
+}
+
+/// 지금까지 발급된 마지막 커밋 버전
+pub fn latest_version() -> u64 {
+    NEXT_VERSION.load(Ordering::SeqCst) - 1
+}
+
+static NEXT_REVISION: AtomicU64 = AtomicU64::new(1);
+
+/// 아이템 revision (프로세스 전체에서 증가: undo 후 다시 수정해도 이전 값과 겹치지 않음)
+pub fn next_revision() -> u64 {
+    NEXT_REVISION.fetch_add(1, Ordering::SeqCst)
+}
+
+/// 스냅샷이 고정한 버전 목록 (버전 → 스냅샷 수)
+#[derive(Clone, Default)]
+pub struct PinRegistry {
+    pins: Arc<Mutex<BTreeMap<u64, usize>>>,
+}
+
+impl PinRegistry {
+    pub fn pin(&self, version: u64) {
+        *self.pins.lock().unwrap().entry(version).or_default() += 1;
+    }
+
+    pub fn unpin(&self, version: u64) {
+        let mut pins = self.pins.lock().unwrap();
+        if let Some(count) = pins.get_mut(&version) {
+            *count -= 1;
+            if *count == 0 {
+                pins.remove(&version);
+            }
+        }
+    }
+
+    /// 가장 오래된 고정 버전 (스냅샷이 없으면 None)
+    pub fn oldest(&self) -> Option<u64> {
+        self.pins.lock().unwrap().keys().next().copied()
+    }
+
+    pub fn count(&self) -> usize {
+        self.pins.lock().unwrap().values().sum()
+    }
+}
+
+/// 키별 커밋된 값의 버전 목록 (None = 삭제됨)
+type Chain = Vec<(u64, Option<Cursor>)>;
+
+/// 테이블 하나의 커밋된 버전 저장소
+pub struct VersionStore<K = i32> {
+    chains: HashMap<K, Chain>,
+    reclaimable: HashSet<K>, // 항목이 둘 이상이거나 삭제로 끝난 키
+    pins: PinRegistry,
+}
+
+impl<K: TableKey> Default for VersionStore<K> {
+    fn default() -> Self {
+        Self::new(PinRegistry::default())
+    }
+}
+
+impl<K: TableKey> VersionStore<K> {
+    pub fn new(pins: PinRegistry) -> Self {
+        VersionStore {
+            chains: HashMap::new(),
+            reclaimable: HashSet::new(),
+            pins,
+        }
+    }
+
+    /// 커밋된 값 기록 (값이 바뀌지 않았으면 무시), 필요 없어진 이전 버전은 바로 회수
+    pub fn record(&mut self, key: K, version: u64, value: Option<Cursor>) {
+        if value.is_none() && !self.chains.contains_key(&key) {
+            return; // 없던 키의 삭제
+        }
+        let chain = self.chains.entry(key.clone()).or_default();
+        if chain.last().is_some_and(|(_, last)| same_value(last, &value)) {
+            return;
+        }
+        let value = value.map(|mut cursor| {
+            cursor.version = version;
+            cursor
+        });
+        chain.push((version, value));
+        self.reclaimable.insert(key.clone());
+        let oldest = self.pins.oldest();
+        self.reclaim_key(key, oldest);
+    }
+
+    /// version 시점의 값
+    pub fn get(&self, key: K, version: u64) -> Option<&Cursor> {
+        let chain = self.chains.get(&key)?;
+        chain.iter().rev().find(|(v, _)| *v <= version)?.1.as_ref()
+    }
+
+    /// version 시점에 존재하는 키 (오름차순)
+    pub fn keys(&self, version: u64) -> Vec<K> {
+        let mut keys: Vec<K> = self.chains.keys().filter(|key| self.get((*key).clone(), version).is_some()).cloned().collect();
+        keys.sort();
+        keys
+    }
+
+    /// 보관 중인 버전 수 (키마다 최신 값 포함)
+    pub fn version_count(&self) -> usize {
+        self.chains.values().map(|chain| chain.len()).sum()
+    }
+
+    /// 어떤 스냅샷도 보지 않는 이전 버전 회수 (회수한 수 반환)
+    pub fn reclaim(&mut self) -> usize {
+        let oldest = self.pins.oldest();
+        let keys: Vec<K> = self.reclaimable.iter().cloned().collect();
+        keys.into_iter().map(|key| self.reclaim_key(key, oldest)).sum()
+    }
+
+    fn reclaim_key(&mut self, key: K, oldest: Option<u64>) -> usize {
+        let Some(chain) = self.chains.get_mut(&key) else {
+            self.reclaimable.remove(&key);
+            return 0;
+        };
+        // 가장 오래된 스냅샷이 보는 항목보다 앞선 항목은 필요 없음
+        let keep_from = match oldest {
+            Some(version) => chain.iter().rposition(|(v, _)| *v <= version).unwrap_or(0),
+            None => chain.len() - 1,
+        };
+        let mut removed = keep_from;
+        chain.drain(..keep_from);
+        if chain.len() == 1 {
+            self.reclaimable.remove(&key);
+            if chain[0].1.is_none() {
+                self.chains.remove(&key); // 삭제만 남은 키
+                removed += 1;
+            }
+        }
+        removed
+    }
+}
+
+fn same_value(a: &Option<Cursor>, b: &Option<Cursor>) -> bool {
+    match (a, b) {
+        (None, None) => true,
+        (Some(a), Some(b)) => Arc::ptr_eq(&a.data, &b.data) && a.param_data == b.param_data && a.param == b.param,
+        _ => false,
+    }
+}
+
+pub type SharedVersions<K = i32> = Arc<RwLock<VersionStore<K>>>;
+
+/// 커밋된 한 버전에 고정된 읽기 전용 뷰 (쓰기를 막지 않음)
+pub struct SnapshotView {
+    version: u64,
+    tables: HashMap<u16, SharedVersions>,
+    pins: PinRegistry,
+}
+
+impl SnapshotView {
+    pub fn new(version: u64, tables: HashMap<u16, SharedVersions>, pins: PinRegistry) -> Self {
+        pins.pin(version);
+        SnapshotView { version, tables, pins }
+    }
+
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// 고정된 버전의 아이템 조회
+    pub fn get(&self, table_type: u16, key: i32) -> Option<Cursor> {
+        let store = self.tables.get(&table_type)?.read().unwrap();
+        store.get(key, self.version).cloned()
+    }
+
+    /// 고정된 버전의 전체 아이템 (키 순)
+    pub fn items(&self, table_type: u16) -> Vec<Cursor> {
+        let Some(store) = self.tables.get(&table_type) else {
+            return Vec::new();
+        };
+        let store = store.read().unwrap();
+        store
+            .keys(self.version)
+            .into_iter()
+            .filter_map(|key| store.get(key, self.version).cloned())
+            .collect()
+    }
+
+    pub fn count(&self, table_type: u16) -> usize {
+        self.tables
+            .get(&table_type)
+            .map(|store| store.read().unwrap().keys(self.version).len())
+            .unwrap_or(0)
+    }
+
+    pub fn table_types(&self) -> Vec<u16> {
+        let mut types: Vec<u16> = self.tables.keys().copied().collect();
+        types.sort();
+        types
+    }
+}
+
+impl Drop for SnapshotView {
+    fn drop(&mut self) {
+        self.pins.unpin(self.version);
+        for store in self.tables.values() {
+            store.write().unwrap().reclaim();
+        }
+    }
+}
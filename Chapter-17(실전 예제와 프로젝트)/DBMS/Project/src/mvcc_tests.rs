@@ -0,0 +1,89 @@
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use crate::item::DItem;
+    use crate::item_factory::item_factory_mut;
+    use crate::session::Session;
+    use crate::shared_session::SharedSession;
+
+    #[derive(Debug)]
+    struct VersionedItem {
+        key: i32,
+    }
+
+    impl DItem for VersionedItem {
+        fn key(&self) -> i32 { self.key }
+        fn item_type(&self) -> u16 { 600 }
+        fn table_type(&self) -> u16 { 60 }
+        fn serialize(&self, _stream: &mut dyn crate::tx_stream::TxStream, _session: &Session) {}
+    }
+
+    fn new_session() -> Session {
+        item_factory_mut().lock().unwrap().register_type(
+            600,
+            60,
+            Arc::new(|key| Arc::new(VersionedItem { key })),
+            Arc::new(|_item| {}),
+        );
+        let mut session = Session::new();
+        session.register_table(60, 600);
+        session
+    }
+
+    #[test]
+    fn test_snapshot_is_pinned_to_committed_version() {
+        let factory = item_factory_mut();
+        let mut session = new_session();
+        session.get_table_mut(60).unwrap().insert(1, factory);
+        session.commit_all().unwrap();
+
+        let snapshot = session.snapshot();
+        let committed = snapshot.get(60, 1).unwrap();
+        assert!(committed.version > 0 && committed.version <= snapshot.version());
+
+        // 커밋되지 않은 변경도, 이후의 커밋도 보이지 않음
+        session.get_table_mut(60).unwrap().insert(2, factory);
+        assert!(snapshot.get(60, 2).is_none());
+        session.commit_all().unwrap();
+        session.get_table_mut(60).unwrap().remove(1);
+        session.commit_all().unwrap();
+        assert!(snapshot.get(60, 1).is_some());
+        assert!(snapshot.get(60, 2).is_none());
+        assert_eq!(snapshot.count(60), 1);
+
+        let latest = session.snapshot();
+        assert_eq!(latest.items(60).iter().map(|c| c.key()).collect::<Vec<_>>(), vec![2]);
+        assert_eq!(session.snapshot_count(), 2);
+
+        // 스냅샷이 모두 닫히면 이전 버전 회수
+        assert!(session.version_count() > 1);
+        drop(snapshot);
+        drop(latest);
+        assert_eq!(session.snapshot_count(), 0);
+        assert_eq!(session.version_count(), 1);
+
+        // undo 도 새 커밋 버전
+        session.undo_all().unwrap();
+        assert!(session.snapshot().get(60, 1).is_some());
+    }
+
+    #[test]
+    fn test_snapshot_reads_do_not_wait_for_writers() {
+        let shared = SharedSession::new(new_session());
+        let mut setup = shared.begin();
+        setup.insert(60, 10).unwrap();
+        setup.commit().unwrap();
+
+        // 배타 잠금을 가진 쓰기 트랜잭션이 진행 중이어도 스냅샷은 바로 읽음
+        let mut writer = shared.begin();
+        writer.remove(60, 10).unwrap();
+        writer.insert(60, 11).unwrap();
+        let snapshot = shared.snapshot();
+        assert!(snapshot.get(60, 10).is_some());
+        assert!(snapshot.get(60, 11).is_none());
+
+        writer.commit().unwrap();
+        assert!(snapshot.get(60, 10).is_some());
+        assert!(shared.snapshot().get(60, 11).is_some());
+    }
+}
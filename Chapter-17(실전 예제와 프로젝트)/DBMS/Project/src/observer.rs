@@ -0,0 +1,181 @@
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::define::TxAction;
+use crate::item::Cursor;
+use crate::key::TableKey;
+use crate::tx_delta_list::TxDeltaList;
+
+static NEXT_SUBSCRIPTION: AtomicU64 = AtomicU64::new(1);
+
+/// 변경 종류
+#[derive(Clone, Debug)]
+pub enum ChangeKind {
+    Inserted(Cursor),
+    Removed(Cursor),
+    Modified { before: Cursor, after: Cursor },
+}
+
+/// 변경을 일으킨 동작
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChangeSource {
+    Commit,
+    Undo,
+    Redo,
+}
+
+/// 테이블 하나의 변경 하나
+#[derive(Clone, Debug)]
+pub struct ChangeEvent {
+    pub table_type: u16,
+    pub change: ChangeKind,
+}
+
+impl ChangeEvent {
+    /// 액션이 저장소에 미친 효과로 이벤트 생성 (Cancelled 는 None)
+    pub fn from_action(table_type: u16, action: &TxAction) -> Option<Self> {
+        let change = match action {
+            TxAction::Insert(c) => ChangeKind::Inserted(c.clone()),
+            TxAction::Remove(c) => ChangeKind::Removed(c.clone()),
+            TxAction::Modify { before, after } => ChangeKind::Modified {
+                before: before.clone(),
+                after: after.clone(),
+            },
+            TxAction::Cancelled => return None,
+        };
+        Some(ChangeEvent { table_type, change })
+    }
+
+    pub fn key(&self) -> i32 {
+        match &self.change {
+            ChangeKind::Inserted(c) | ChangeKind::Removed(c) => c.key(),
+            ChangeKind::Modified { after, .. } => after.key(),
+        }
+    }
+
+    /// 키 타입 K 의 키 (i32 가 아닌 키 타입 테이블용)
+    pub fn key_as<K: TableKey>(&self) -> Option<K> {
+        match &self.change {
+            ChangeKind::Inserted(c) | ChangeKind::Removed(c) => c.key_as(),
+            ChangeKind::Modified { after, .. } => after.key_as(),
+        }
+    }
+}
+
+/// 커밋(또는 undo/redo) 한 번에 해당하는 이벤트 묶음
+#[derive(Clone, Debug)]
+pub struct ChangeBatch {
+    pub source: ChangeSource,
+    pub group: u64,
+    pub label: String,
+    pub events: Vec<ChangeEvent>,
+}
+
+impl ChangeBatch {
+    pub fn new(source: ChangeSource, group: u64, label: &str) -> Self {
+        ChangeBatch {
+            source,
+            group,
+            label: label.to_string(),
+            events: Vec::new(),
+        }
+    }
+
+    /// 델타의 액션들을 이벤트로 추가 (델타는 저장소에 적용된 방향이어야 함)
+    pub fn add_delta<K: TableKey>(&mut self, table_type: u16, delta: &TxDeltaList<K>) {
+        self.events
+            .extend(delta.iter().filter_map(|action| ChangeEvent::from_action(table_type, action)));
+    }
+}
+
+pub type ChangeCallback = Arc<dyn Fn(&ChangeBatch) + Send + Sync>;
+
+/// 바로 호출하지 않고 모아 둔 알림 (SharedSession 이 세션 잠금을 푼 뒤 전달)
+pub type Outbox = Arc<Mutex<Vec<(ChangeCallback, ChangeBatch)>>>;
+
+/// 구독 해지용 번호
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SubscriptionId(u64);
+
+struct Subscriber {
+    id: SubscriptionId,
+    table_type: Option<u16>, // None = 모든 테이블
+    callback: ChangeCallback,
+}
+
+/// 구독자 목록
+#[derive(Default)]
+pub struct ObserverList {
+    subscribers: Vec<Subscriber>,
+    outbox: Option<Outbox>, // 있으면 콜백을 호출하는 대신 여기에 모음
+}
+
+impl ObserverList {
+    pub fn new() -> Self {
+        ObserverList::default()
+    }
+
+    /// 구독 (table_type 이 있으면 해당 테이블 이벤트만 전달)
+    pub fn subscribe(&mut self, table_type: Option<u16>, callback: ChangeCallback) -> SubscriptionId {
+        let id = SubscriptionId(NEXT_SUBSCRIPTION.fetch_add(1, Ordering::Relaxed));
+        self.subscribers.push(Subscriber {
+            id,
+            table_type,
+            callback,
+        });
+        id
+    }
+
+    pub fn unsubscribe(&mut self, id: SubscriptionId) -> bool {
+        let before = self.subscribers.len();
+        self.subscribers.retain(|s| s.id != id);
+        self.subscribers.len() != before
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.subscribers.is_empty()
+    }
+
+    /// 알림을 outbox 에 모으도록 설정 (None 이면 바로 호출)
+    pub fn set_outbox(&mut self, outbox: Option<Outbox>) {
+        self.outbox = outbox;
+    }
+
+    pub fn outbox(&self) -> Option<Outbox> {
+        self.outbox.clone()
+    }
+
+    fn send(&self, callback: &ChangeCallback, batch: ChangeBatch) {
+        match &self.outbox {
+            Some(outbox) => outbox.lock().unwrap().push((callback.clone(), batch)),
+            None => callback(&batch),
+        }
+    }
+
+    /// 묶음 전달: 구독 범위의 이벤트가 없으면 호출하지 않음
+    pub fn notify(&self, batch: &ChangeBatch) {
+        if batch.events.is_empty() {
+            return;
+        }
+        for subscriber in &self.subscribers {
+            match subscriber.table_type {
+                None => self.send(&subscriber.callback, batch.clone()),
+                Some(table_type) => {
+                    let events: Vec<ChangeEvent> = batch
+                        .events
+                        .iter()
+                        .filter(|e| e.table_type == table_type)
+                        .cloned()
+                        .collect();
+                    if !events.is_empty() {
+                        self.send(&subscriber.callback, ChangeBatch {
+                            events,
+                            label: batch.label.clone(),
+                            ..*batch
+                        });
+                    }
+                }
+            }
+        }
+    }
+}
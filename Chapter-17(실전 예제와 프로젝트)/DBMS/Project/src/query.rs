@@ -0,0 +1,431 @@
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
+use std::ops::{Bound, RangeBounds};
+use std::sync::Arc;
+
+use crate::index::{IndexExtractor, IndexValue};
+use crate::item::{Cursor, DItem};
+use crate::item_factory::item_factory;
+use crate::session::Session;
+use crate::table::Table;
+
+/// 아이템에서 값을 뽑는 이름 붙은 필드 (같은 이름의 보조 인덱스가 있으면 조회에 사용)
+#[derive(Clone)]
+pub struct Field {
+    pub name: String,
+    extract: IndexExtractor,
+}
+
+impl Field {
+    pub fn new(name: &str, extract: impl Fn(&dyn DItem) -> Option<IndexValue> + Send + Sync + 'static) -> Self {
+        Field {
+            name: name.to_string(),
+            extract: Arc::new(extract),
+        }
+    }
+
+    /// 등록된 스키마의 필드로 만들기 (순서 비교가 안 되는 Float, Bytes 필드는 값이 None)
+    pub fn reflected(item_type: u16, name: &str) -> Option<Self> {
+        let get = item_factory().lock().ok()?.field_def(item_type, name)?.get.clone();
+        Some(Field::new(name, move |item| get(item)?.to_index_value()))
+    }
+
+    pub fn value(&self, cursor: &Cursor) -> Option<IndexValue> {
+        (self.extract)(cursor.data.as_ref())
+    }
+
+    /// 같은 추출 함수로 인덱스를 만들 때 사용
+    pub fn extractor(&self) -> IndexExtractor {
+        self.extract.clone()
+    }
+}
+
+#[derive(Clone)]
+enum Condition {
+    Eq(Field, IndexValue),
+    Range(Field, Bound<IndexValue>, Bound<IndexValue>),
+}
+
+impl Condition {
+    fn field(&self) -> &Field {
+        match self {
+            Condition::Eq(field, _) | Condition::Range(field, _, _) => field,
+        }
+    }
+
+    fn matches(&self, cursor: &Cursor) -> bool {
+        let Some(value) = self.field().value(cursor) else {
+            return false;
+        };
+        match self {
+            Condition::Eq(_, expected) => value == *expected,
+            Condition::Range(_, lo, hi) => (lo.as_ref(), hi.as_ref()).contains(&value),
+        }
+    }
+}
+
+/// 실행 계획: 후보 키를 어디서 가져오는지
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum QueryPlan {
+    Index(String), // 보조 인덱스
+    KeyRange,      // 키 범위 (Ordered 저장소는 B-tree 범위 조회)
+    Scan,          // 전체 조회
+}
+
+/// 집계 결과 (sum 은 정수 값만 더함, i64 값을 모두 더해도 넘치지 않도록 i128)
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Aggregate {
+    pub count: usize,
+    pub sum: i128,
+    pub min: Option<IndexValue>,
+    pub max: Option<IndexValue>,
+}
+
+impl Aggregate {
+    fn add(&mut self, value: IndexValue) {
+        self.count += 1;
+        if let IndexValue::Int(v) = value {
+            self.sum += v as i128;
+        }
+        if self.min.as_ref().is_none_or(|min| value < *min) {
+            self.min = Some(value.clone());
+        }
+        if self.max.as_ref().is_none_or(|max| value > *max) {
+            self.max = Some(value);
+        }
+    }
+}
+
+type Predicate<'a> = Box<dyn Fn(&Cursor) -> bool + 'a>;
+
+enum Order {
+    Key { desc: bool },
+    Field { field: Field, desc: bool },
+}
+
+/// 테이블 하나에 대한 조회: 조건, 정렬, 건너뛰기, 개수 제한을 이어 붙인 뒤 실행
+pub struct Query<'a> {
+    session: &'a Session,
+    table_type: u16,
+    key_range: Option<(Bound<i32>, Bound<i32>)>,
+    conditions: Vec<Condition>,
+    filters: Vec<Predicate<'a>>,
+    order: Option<Order>,
+    offset: usize,
+    limit: Option<usize>,
+}
+
+impl<'a> Query<'a> {
+    pub fn new(session: &'a Session, table_type: u16) -> Self {
+        Query {
+            session,
+            table_type,
+            key_range: None,
+            conditions: Vec::new(),
+            filters: Vec::new(),
+            order: None,
+            offset: 0,
+            limit: None,
+        }
+    }
+
+    /// 키 범위로 제한
+    pub fn key_range<R: RangeBounds<i32>>(mut self, range: R) -> Self {
+        self.key_range = Some((range.start_bound().cloned(), range.end_bound().cloned()));
+        self
+    }
+
+    /// 필드 값이 같은 아이템
+    pub fn where_eq(mut self, field: &Field, value: impl Into<IndexValue>) -> Self {
+        self.conditions.push(Condition::Eq(field.clone(), value.into()));
+        self
+    }
+
+    /// 필드 값이 범위에 드는 아이템
+    pub fn where_range<R: RangeBounds<IndexValue>>(mut self, field: &Field, range: R) -> Self {
+        self.conditions.push(Condition::Range(
+            field.clone(),
+            range.start_bound().cloned(),
+            range.end_bound().cloned(),
+        ));
+        self
+    }
+
+    /// 임의 조건
+    pub fn filter(mut self, predicate: impl Fn(&Cursor) -> bool + 'a) -> Self {
+        self.filters.push(Box::new(predicate));
+        self
+    }
+
+    pub fn order_by_key(mut self, desc: bool) -> Self {
+        self.order = Some(Order::Key { desc });
+        self
+    }
+
+    /// 필드 값 순 정렬 (값이 없는 아이템은 맨 뒤, 같은 값은 키 순)
+    pub fn order_by(mut self, field: &Field, desc: bool) -> Self {
+        self.order = Some(Order::Field { field: field.clone(), desc });
+        self
+    }
+
+    pub fn offset(mut self, offset: usize) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// 실행 계획: 인덱스가 있는 첫 조건 → 키 범위 → 전체 조회
+    pub fn plan(&self) -> QueryPlan {
+        match self.index_condition() {
+            Some(i) => QueryPlan::Index(self.conditions[i].field().name.clone()),
+            None if self.key_range.is_some() => QueryPlan::KeyRange,
+            None => QueryPlan::Scan,
+        }
+    }
+
+    /// 결과 아이템
+    pub fn collect(&self) -> Vec<Cursor> {
+        self.run().into_iter().cloned().collect()
+    }
+
+    pub fn keys(&self) -> Vec<i32> {
+        self.run().into_iter().map(|c| c.key()).collect()
+    }
+
+    /// 아이템마다 값 하나로 변환
+    pub fn map<T>(&self, f: impl Fn(&Cursor) -> T) -> Vec<T> {
+        self.run().into_iter().map(f).collect()
+    }
+
+    /// 필드 값만 골라냄: (키, 필드 순서대로의 값)
+    pub fn project(&self, fields: &[&Field]) -> Vec<(i32, Vec<Option<IndexValue>>)> {
+        self.map(|c| (c.key(), fields.iter().map(|f| f.value(c)).collect()))
+    }
+
+    pub fn count(&self) -> usize {
+        self.run().len()
+    }
+
+    /// 필드 값 집계 (값이 없는 아이템은 제외)
+    pub fn aggregate(&self, field: &Field) -> Aggregate {
+        let mut result = Aggregate::default();
+        for cursor in self.run() {
+            if let Some(value) = field.value(cursor) {
+                result.add(value);
+            }
+        }
+        result
+    }
+
+    pub fn sum(&self, field: &Field) -> i128 {
+        self.aggregate(field).sum
+    }
+
+    pub fn min(&self, field: &Field) -> Option<IndexValue> {
+        self.aggregate(field).min
+    }
+
+    pub fn max(&self, field: &Field) -> Option<IndexValue> {
+        self.aggregate(field).max
+    }
+
+    /// group 필드 값별로 value 필드 집계
+    pub fn group_by(&self, group: &Field, value: &Field) -> BTreeMap<IndexValue, Aggregate> {
+        let mut groups: BTreeMap<IndexValue, Aggregate> = BTreeMap::new();
+        for cursor in self.run() {
+            if let (Some(g), Some(v)) = (group.value(cursor), value.value(cursor)) {
+                groups.entry(g).or_default().add(v);
+            }
+        }
+        groups
+    }
+
+    fn table(&self) -> Option<&'a Table> {
+        self.session.get_table(self.table_type)
+    }
+
+    fn index_condition(&self) -> Option<usize> {
+        let table = self.table()?;
+        self.conditions.iter().position(|c| table.index(&c.field().name).is_some())
+    }
+
+    fn run(&self) -> Vec<&'a Cursor> {
+        let Some(Order::Field { field, desc }) = &self.order else {
+            return self.iter().collect();
+        };
+
+        let mut keyed: Vec<(Option<IndexValue>, &Cursor)> = self.filtered(false).map(|c| (field.value(c), c)).collect();
+        keyed.sort_by(|(a, ca), (b, cb)| {
+            let by_value = match (a, b) {
+                (Some(a), Some(b)) if *desc => b.cmp(a),
+                (Some(a), Some(b)) => a.cmp(b),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            };
+            by_value.then(ca.key().cmp(&cb.key()))
+        });
+        keyed.into_iter().map(|(_, c)| c).skip(self.offset).take(self.limit.unwrap_or(usize::MAX)).collect()
+    }
+
+    /// 결과를 차례로 만드는 반복자 (필드 값 정렬은 모두 모아 정렬해야 하므로 run 결과를 돌려줌)
+    fn iter(&self) -> Box<dyn Iterator<Item = &'a Cursor> + '_> {
+        if matches!(self.order, Some(Order::Field { .. })) {
+            return Box::new(self.run().into_iter());
+        }
+        let desc = matches!(self.order, Some(Order::Key { desc: true }));
+        Box::new(self.filtered(desc).skip(self.offset).take(self.limit.unwrap_or(usize::MAX)))
+    }
+
+    /// 조건을 만족하는 아이템 (키 순, desc 면 역순)
+    fn filtered(&self, desc: bool) -> Box<dyn Iterator<Item = &'a Cursor> + '_> {
+        let Some(table) = self.table() else {
+            return Box::new(std::iter::empty());
+        };
+
+        // 후보: 인덱스 → 키 범위 → 전체 (인덱스 후보는 키 순으로 정리)
+        let indexed = self.index_condition();
+        let candidates: Box<dyn Iterator<Item = &'a Cursor> + 'a> = match indexed {
+            Some(i) => {
+                let index = table.index(&self.conditions[i].field().name).unwrap();
+                let mut keys = match &self.conditions[i] {
+                    Condition::Eq(_, value) => index.find(value),
+                    Condition::Range(_, lo, hi) => index.range((lo.clone(), hi.clone())),
+                };
+                keys.sort();
+                if desc {
+                    keys.reverse();
+                }
+                Box::new(keys.into_iter().filter_map(move |key| table.get(key)))
+            }
+            None => {
+                let range = self.key_range.unwrap_or((Bound::Unbounded, Bound::Unbounded));
+                if desc { table.items.range_rev(range) } else { table.items.range(range) }
+            }
+        };
+
+        let key_range = indexed.and(self.key_range);
+        Box::new(candidates.filter(move |c| {
+            key_range.is_none_or(|range| range.contains(&c.key()))
+                && self.conditions.iter().enumerate().all(|(i, cond)| Some(i) == indexed || cond.matches(c))
+                && self.filters.iter().all(|f| f(c))
+        }))
+    }
+}
+
+/// 조인 기준 값: 아이템 키 또는 필드 값
+#[derive(Clone)]
+pub enum JoinKey {
+    Key,
+    Field(Field),
+}
+
+impl JoinKey {
+    fn value(&self, cursor: &Cursor) -> Option<IndexValue> {
+        match self {
+            JoinKey::Key => Some(IndexValue::Int(cursor.key() as i64)),
+            JoinKey::Field(field) => field.value(cursor),
+        }
+    }
+}
+
+/// 조인 실행 방식
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum JoinPlan {
+    Hash,                // 오른쪽 전체로 해시 테이블을 만든 뒤 왼쪽을 차례로 대조
+    KeyLookup,           // 왼쪽 행마다 오른쪽 키로 바로 조회
+    IndexLookup(String), // 왼쪽 행마다 오른쪽 보조 인덱스 조회
+}
+
+pub type JoinRows<'a> = Box<dyn Iterator<Item = (&'a Cursor, &'a Cursor)> + 'a>;
+
+/// 두 테이블의 조인: 왼쪽은 조회 결과, 오른쪽은 테이블 전체
+pub struct Join<'a> {
+    left: Query<'a>,
+    right_table: u16,
+    left_on: JoinKey,
+    right_on: JoinKey,
+}
+
+impl<'a> Query<'a> {
+    /// 이 조회 결과(왼쪽)를 다른 테이블(오른쪽)과 조인
+    pub fn join(self, right_table: u16, left_on: JoinKey, right_on: JoinKey) -> Join<'a> {
+        Join {
+            left: self,
+            right_table,
+            left_on,
+            right_on,
+        }
+    }
+}
+
+impl<'a> Join<'a> {
+    /// 오른쪽이 키 조인이거나 같은 이름의 인덱스가 있으면 인덱스 조회, 아니면 해시 조인
+    pub fn plan(&self) -> JoinPlan {
+        match &self.right_on {
+            JoinKey::Key => JoinPlan::KeyLookup,
+            JoinKey::Field(field) => match self.right().and_then(|t| t.index(&field.name)) {
+                Some(_) => JoinPlan::IndexLookup(field.name.clone()),
+                None => JoinPlan::Hash,
+            },
+        }
+    }
+
+    /// 계획에 따라 실행한 (왼쪽, 오른쪽) 쌍 (왼쪽 순서, 같은 왼쪽 안에서는 오른쪽 키 순)
+    /// 왼쪽은 필요한 만큼만 차례로 읽음
+    pub fn rows(&self) -> JoinRows<'_> {
+        match self.plan() {
+            JoinPlan::Hash => self.hash_join(),
+            _ => self.index_join(),
+        }
+    }
+
+    /// 해시 조인: 오른쪽으로만 해시 테이블을 미리 만듦
+    pub fn hash_join(&self) -> JoinRows<'_> {
+        let Some(right) = self.right() else {
+            return Box::new(std::iter::empty());
+        };
+        let mut built: HashMap<IndexValue, Vec<&'a Cursor>> = HashMap::new();
+        for cursor in right.items.range(..) {
+            if let Some(value) = self.right_on.value(cursor) {
+                built.entry(value).or_default().push(cursor);
+            }
+        }
+        let left_on = self.left_on.clone();
+        Box::new(self.left.iter().flat_map(move |l| {
+            let matches = left_on.value(l).and_then(|v| built.get(&v).cloned()).unwrap_or_default();
+            matches.into_iter().map(move |r| (l, r))
+        }))
+    }
+
+    /// 인덱스 중첩 루프 조인 (오른쪽에 쓸 인덱스가 없으면 왼쪽 행마다 오른쪽 전체를 훑음)
+    pub fn index_join(&self) -> JoinRows<'_> {
+        let Some(right) = self.right() else {
+            return Box::new(std::iter::empty());
+        };
+        let left_on = self.left_on.clone();
+        let right_on = self.right_on.clone();
+        Box::new(self.left.iter().flat_map(move |l| {
+            let matches: Vec<&'a Cursor> = match (left_on.value(l), &right_on) {
+                (None, _) => Vec::new(),
+                (Some(IndexValue::Int(v)), JoinKey::Key) => {
+                    i32::try_from(v).ok().and_then(|key| right.get(key)).into_iter().collect()
+                }
+                (Some(_), JoinKey::Key) => Vec::new(),
+                (Some(v), JoinKey::Field(field)) => match right.index(&field.name) {
+                    Some(index) => index.find(&v).into_iter().filter_map(|key| right.get(key)).collect(),
+                    None => right.items.range(..).filter(|r| field.value(r).as_ref() == Some(&v)).collect(),
+                },
+            };
+            matches.into_iter().map(move |r| (l, r))
+        }))
+    }
+
+    fn right(&self) -> Option<&'a Table> {
+        self.left.session.get_table(self.right_table)
+    }
+}
@@ -0,0 +1,106 @@
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use crate::hashset::StorageMode;
+    use crate::index::IndexValue;
+    use crate::item::DItem;
+    use crate::item_factory::item_factory_mut;
+    use crate::query::{Field, QueryPlan};
+    use crate::session::Session;
+
+    #[derive(Debug)]
+    struct Employee {
+        key: i32,
+        dept: String,
+        salary: i64,
+    }
+
+    impl DItem for Employee {
+        fn key(&self) -> i32 { self.key }
+        fn item_type(&self) -> u16 { 900 }
+        fn table_type(&self) -> u16 { 90 }
+        fn serialize(&self, _stream: &mut dyn crate::tx_stream::TxStream, _session: &Session) {}
+    }
+
+    fn dept() -> Field {
+        Field::new("dept", |item| item.downcast_ref::<Employee>().map(|e| IndexValue::from(e.dept.as_str())))
+    }
+
+    fn salary() -> Field {
+        Field::new("salary", |item| item.downcast_ref::<Employee>().map(|e| IndexValue::from(e.salary)))
+    }
+
+    fn new_session() -> Session {
+        let factory = item_factory_mut();
+        factory.lock().unwrap().register_type(
+            900,
+            90,
+            Arc::new(|key| Arc::new(Employee { key, dept: String::new(), salary: 0 })),
+            Arc::new(|_item| {}),
+        );
+        let mut session = Session::new();
+        session.register_table_with(90, 900, StorageMode::Ordered);
+        let table = session.get_table_mut(90).unwrap();
+        let rows = [(1, "dev", 300), (2, "ops", 200), (3, "dev", 500), (4, "sales", 100), (5, "dev", 400)];
+        for (key, dept, salary) in rows {
+            table.insert(key, factory);
+            table.modify(key, Arc::new(Employee { key, dept: dept.to_string(), salary }));
+        }
+        session.commit_all().unwrap();
+        session
+    }
+
+    #[test]
+    fn test_query_filter_sort_limit_project() {
+        let session = new_session();
+
+        let top = session.query(90).where_eq(&dept(), "dev").order_by(&salary(), true).limit(2);
+        assert_eq!(top.plan(), QueryPlan::Scan);
+        assert_eq!(top.keys(), vec![3, 5]);
+
+        let ranged = session.query(90).key_range(2..=4).filter(|c| c.key() != 3);
+        assert_eq!(ranged.plan(), QueryPlan::KeyRange);
+        assert_eq!(ranged.keys(), vec![2, 4]);
+        let (low, high) = (2, 4);
+        assert!(session.query(90).key_range(high..low).keys().is_empty());
+        assert_eq!(session.query(90).order_by_key(true).offset(1).limit(2).keys(), vec![4, 3]);
+
+        let projected = session.query(90).where_range(&salary(), IndexValue::from(400)..).project(&[&dept(), &salary()]);
+        assert_eq!(projected, vec![
+            (3, vec![Some("dev".into()), Some(IndexValue::Int(500))]),
+            (5, vec![Some("dev".into()), Some(IndexValue::Int(400))]),
+        ]);
+    }
+
+    #[test]
+    fn test_query_uses_index_and_aggregates() {
+        let mut session = new_session();
+        let field = dept();
+        session.get_table_mut(90).unwrap().create_index(&field.name, field.extractor());
+
+        let dev = session.query(90).where_eq(&dept(), "dev").where_range(&salary(), IndexValue::from(350)..);
+        assert_eq!(dev.plan(), QueryPlan::Index("dept".to_string()));
+        assert_eq!(dev.keys(), vec![3, 5]);
+
+        let all = session.query(90);
+        assert_eq!(all.count(), 5);
+        assert_eq!(all.sum(&salary()), 1500);
+        assert_eq!(all.min(&salary()), Some(IndexValue::Int(100)));
+        assert_eq!(all.max(&dept()), Some("sales".into()));
+
+        let groups = session.query(90).group_by(&dept(), &salary());
+        assert_eq!(groups.len(), 3);
+        let dev = &groups[&"dev".into()];
+        assert_eq!((dev.count, dev.sum), (3, 1200));
+        assert_eq!(dev.max, Some(IndexValue::Int(500)));
+        assert_eq!(groups[&"ops".into()].sum, 200);
+
+        // i64 범위를 넘는 합계도 패닉 없이 계산
+        let mut session = new_session();
+        let table = session.get_table_mut(90).unwrap();
+        for key in [1, 3] {
+            table.modify(key, Arc::new(Employee { key, dept: "dev".to_string(), salary: i64::MAX }));
+        }
+        assert_eq!(session.query(90).sum(&salary()), 2 * i64::MAX as i128 + 700);
+    }
+}
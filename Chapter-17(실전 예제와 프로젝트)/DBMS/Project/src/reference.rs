@@ -0,0 +1,171 @@
+use std::fmt;
+use std::io;
+use std::sync::Arc;
+
+use crate::item::{Cursor, DItem};
+use crate::key::TableKey;
+use crate::table::Table;
+
+/// 참조 대상이 삭제될 때의 처리
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DeletePolicy {
+    #[default]
+    Restrict, // 참조하는 아이템이 있으면 커밋 거부
+    Cascade,  // 참조하는 아이템도 함께 삭제
+    SetNull,  // 참조하는 아이템의 참조를 비움
+}
+
+/// 아이템이 가리키는 대상 키 (None = 참조 없음)
+pub type ReferenceExtractor<K = i32> = Arc<dyn Fn(&dyn DItem) -> Option<K> + Send + Sync>;
+
+/// 참조를 비운 새 아이템 (SetNull 정책)
+pub type ReferenceClearer = Arc<dyn Fn(&dyn DItem) -> Option<Arc<dyn DItem>> + Send + Sync>;
+
+/// from_table 의 from_item_type 아이템 → to_table 의 to_item_type 아이템 키 (K) 참조 선언
+/// (from_table 에 같은 이름의 보조 인덱스가 있으면 참조하는 아이템 검색에 사용)
+/// Session 은 i32 키 참조만 커밋 때 검사하고, 다른 키 타입 테이블은 referrers/dangling 으로 직접 검사
+#[derive(Clone)]
+pub struct Reference<K: TableKey = i32> {
+    pub name: String,
+    pub from_table: u16,
+    pub from_item_type: u16,
+    pub to_table: u16,
+    pub to_item_type: u16,
+    pub on_delete: DeletePolicy,
+    extract: ReferenceExtractor<K>,
+    clear: Option<ReferenceClearer>,
+}
+
+impl<K: TableKey> Reference<K> {
+    pub fn new(
+        name: &str,
+        (from_table, from_item_type): (u16, u16),
+        (to_table, to_item_type): (u16, u16),
+        extract: impl Fn(&dyn DItem) -> Option<K> + Send + Sync + 'static,
+    ) -> Self {
+        Reference {
+            name: name.to_string(),
+            from_table,
+            from_item_type,
+            to_table,
+            to_item_type,
+            on_delete: DeletePolicy::Restrict,
+            extract: Arc::new(extract),
+            clear: None,
+        }
+    }
+
+    pub fn cascade(mut self) -> Self {
+        self.on_delete = DeletePolicy::Cascade;
+        self
+    }
+
+    pub fn set_null(mut self, clear: impl Fn(&dyn DItem) -> Option<Arc<dyn DItem>> + Send + Sync + 'static) -> Self {
+        self.on_delete = DeletePolicy::SetNull;
+        self.clear = Some(Arc::new(clear));
+        self
+    }
+
+    /// 참조하는 아이템이 가리키는 대상 키 (from_item_type 이 아닌 아이템은 None)
+    pub fn target(&self, item: &dyn DItem) -> Option<K> {
+        if item.item_type() != self.from_item_type {
+            return None;
+        }
+        (self.extract)(item)
+    }
+
+    /// 두 테이블이 이 참조의 양쪽인지 (테이블 번호와 아이템 타입 모두 일치)
+    pub fn connects<F: TableKey>(&self, from: &Table<F>, to: &Table<K>) -> bool {
+        (from.table_type, from.item_type) == (self.from_table, self.from_item_type)
+            && (to.table_type, to.item_type) == (self.to_table, self.to_item_type)
+    }
+
+    /// from 에서 target 을 가리키는 아이템 (키 순, 같은 이름의 인덱스가 있고 키를 인덱스 값으로 바꿀 수 있으면 사용)
+    pub fn referrers<'t, F: TableKey>(&self, from: &'t Table<F>, target: &K) -> Vec<&'t Cursor> {
+        let cursors: Vec<&Cursor> = match (from.index(&self.name), target.index_value()) {
+            (Some(_), Some(value)) => from.find_by_index(&self.name, &value),
+            _ => from.items.range(..).collect(),
+        };
+        cursors
+            .into_iter()
+            .filter(|c| self.target(c.data.as_ref()).as_ref() == Some(target))
+            .collect()
+    }
+
+    /// from 의 커밋되지 않은 변경 중 to 에 없는 대상을 가리키는 첫 아이템: (키, 대상 키)
+    pub fn dangling<F: TableKey>(&self, from: &Table<F>, to: &Table<K>) -> Option<(F, K)> {
+        let mut keys: Vec<F> = from.tx.current().keys.iter().cloned().collect();
+        keys.sort();
+        keys.into_iter().find_map(|key| {
+            let target = self.target(from.get(key.clone())?.data.as_ref())?;
+            to.get(target.clone()).is_none().then_some((key, target))
+        })
+    }
+
+    /// SetNull 정책의 새 아이템
+    pub fn cleared(&self, item: &dyn DItem) -> Option<Arc<dyn DItem>> {
+        (self.clear.as_ref()?)(item)
+    }
+}
+
+/// 참조 무결성 위반 (커밋 거부)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ReferenceError {
+    /// 삭제한 대상을 아직 참조하는 아이템이 있음
+    Restricted {
+        reference: String,
+        table_type: u16,
+        key: i32,
+        referrer_table: u16,
+        referrer_key: i32,
+    },
+    /// 연쇄 삭제/참조 비움으로 바꿀 아이템을 다른 트랜잭션이 잠금 (다시 시도 가능)
+    Locked {
+        reference: String,
+        table_type: u16,
+        key: i32,
+        referrer_table: u16,
+        referrer_key: i32,
+    },
+    /// 없는 대상을 참조
+    Dangling {
+        reference: String,
+        table_type: u16,
+        key: i32,
+        target_table: u16,
+        target_key: i32,
+    },
+}
+
+impl fmt::Display for ReferenceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReferenceError::Restricted { reference, table_type, key, referrer_table, referrer_key } => write!(
+                f,
+                "reference {}: table {} key {} is still referenced by table {} key {}",
+                reference, table_type, key, referrer_table, referrer_key
+            ),
+            ReferenceError::Locked { reference, table_type, key, referrer_table, referrer_key } => write!(
+                f,
+                "reference {}: deleting table {} key {} needs table {} key {}, which another transaction has locked",
+                reference, table_type, key, referrer_table, referrer_key
+            ),
+            ReferenceError::Dangling { reference, table_type, key, target_table, target_key } => write!(
+                f,
+                "reference {}: table {} key {} points to missing table {} key {}",
+                reference, table_type, key, target_table, target_key
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ReferenceError {}
+
+impl From<ReferenceError> for io::Error {
+    fn from(e: ReferenceError) -> Self {
+        match e {
+            ReferenceError::Locked { .. } => io::Error::new(io::ErrorKind::WouldBlock, e),
+            _ => io::Error::other(e),
+        }
+    }
+}
@@ -0,0 +1,180 @@
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::io;
+    use std::sync::Arc;
+    use crate::index::IndexValue;
+    use crate::item::{Cursor, DItem};
+    use crate::item_factory::item_factory_mut;
+    use crate::reference::{Reference, ReferenceError};
+    use crate::session::Session;
+    use crate::table::Table;
+
+    #[derive(Debug, DItem)]
+    #[ditem(item_type = 1700, table_type = 170)]
+    struct Vertex {
+        #[key]
+        id: i32,
+    }
+
+    #[derive(Clone, Debug, PartialEq, DItem)]
+    #[ditem(item_type = 1701, table_type = 171)]
+    struct Edge {
+        #[key]
+        id: i32,
+        start: i32,
+        end: Option<i32>,
+    }
+
+    #[derive(Debug, DItem)]
+    #[ditem(item_type = 1702, table_type = 172)]
+    struct Label {
+        #[key]
+        id: i32,
+        vertex: i32,
+    }
+
+    fn edge(item: &dyn DItem) -> Option<&Edge> {
+        item.downcast_ref::<Edge>()
+    }
+
+    fn new_session() -> Session {
+        let factory = item_factory_mut();
+        Vertex::register(factory);
+        Edge::register(factory);
+        Label::register(factory);
+
+        let mut session = Session::new();
+        session.register_table(170, 1700);
+        session.register_table(171, 1701);
+        session.register_table(172, 1702);
+        assert!(session.add_reference(Reference::new("start", (171, 1701), (170, 1700), |item| edge(item).map(|e| e.start)).cascade()));
+        assert!(session.add_reference(
+            Reference::new("end", (171, 1701), (170, 1700), |item| edge(item)?.end)
+                .set_null(|item| Some(Arc::new(Edge { end: None, ..edge(item)?.clone() }))),
+        ));
+        assert!(session.add_reference(Reference::new("vertex", (172, 1702), (170, 1700), |item| item.downcast_ref::<Label>().map(|l| l.vertex))));
+
+        let vertices = session.get_table_mut(170).unwrap();
+        vertices.insert(1, factory);
+        vertices.insert(2, factory);
+        let edges = session.get_table_mut(171).unwrap();
+        for (id, start, end) in [(10, 1, 2), (11, 2, 1)] {
+            edges.insert(id, factory);
+            edges.modify(id, Arc::new(Edge { id, start, end: Some(end) }));
+        }
+        session.commit_all().unwrap();
+        session
+    }
+
+    fn reference_error(err: &io::Error) -> Option<&ReferenceError> {
+        err.get_ref()?.downcast_ref::<ReferenceError>()
+    }
+
+    fn end_of(session: &Session, key: i32) -> Option<Option<i32>> {
+        Some(edge(session.get_table(171)?.get(key)?.data.as_ref())?.end)
+    }
+
+    #[test]
+    fn test_cascade_and_set_null_are_undone_together() {
+        let mut session = new_session();
+
+        // 없는 정점을 가리키는 변은 거부
+        let factory = item_factory_mut();
+        let edges = session.get_table_mut(171).unwrap();
+        edges.insert(12, factory);
+        edges.modify(12, Arc::new(Edge { id: 12, start: 9, end: None }));
+        let err = session.commit_all().unwrap_err();
+        assert!(matches!(reference_error(&err), Some(ReferenceError::Dangling { key: 12, target_key: 9, .. })));
+        session.rollback_pending_to(&HashMap::new());
+
+        session.get_table_mut(170).unwrap().remove(1);
+        session.commit_all().unwrap();
+        assert!(session.get_table(171).unwrap().get(10).is_none()); // start = 1 → 연쇄 삭제
+        assert_eq!(end_of(&session, 11), Some(None)); // end = 1 → 비움
+
+        session.undo_all().unwrap();
+        assert!(session.get_table(170).unwrap().get(1).is_some());
+        assert_eq!(end_of(&session, 10), Some(Some(2)));
+        assert_eq!(end_of(&session, 11), Some(Some(1)));
+    }
+
+    #[test]
+    fn test_restrict_rejects_commit_and_keeps_referrers() {
+        let mut session = new_session();
+        let factory = item_factory_mut();
+        let labels = session.get_table_mut(172).unwrap();
+        labels.insert(20, factory);
+        labels.modify(20, Arc::new(Label { id: 20, vertex: 2 }));
+        session.commit_all().unwrap();
+
+        session.get_table_mut(170).unwrap().remove(2);
+        let err = session.commit_all().unwrap_err();
+        assert_eq!(
+            reference_error(&err),
+            Some(&ReferenceError::Restricted {
+                reference: "vertex".to_string(),
+                table_type: 170,
+                key: 2,
+                referrer_table: 172,
+                referrer_key: 20,
+            })
+        );
+        // 정책으로 바꾼 변은 되돌아가고, 사용자의 삭제만 커밋되지 않은 채 남음
+        assert_eq!(end_of(&session, 11), Some(Some(1)));
+        assert_eq!(end_of(&session, 10), Some(Some(2)));
+        assert!(session.get_table(170).unwrap().get(2).is_none());
+
+        session.get_table_mut(172).unwrap().remove(20);
+        session.commit_all().unwrap();
+        assert!(session.get_table(171).unwrap().get(11).is_none());
+        assert_eq!(end_of(&session, 10), Some(None));
+    }
+
+    #[derive(Debug, DItem)]
+    #[ditem(item_type = 2200, table_type = 220)]
+    struct Material {
+        #[key]
+        name: String,
+    }
+
+    #[derive(Debug, DItem)]
+    #[ditem(item_type = 2201, table_type = 221)]
+    struct Part {
+        #[key]
+        name: String,
+        material: String,
+    }
+
+    #[test]
+    fn test_string_keyed_tables_use_reference_checks() {
+        let mut materials: Table<String> = Table::new(220, 2200);
+        materials.insert_item(Arc::new(Material { name: "steel".into() }));
+        materials.commit();
+        let mut parts: Table<String> = Table::new(221, 2201);
+        for (name, material) in [("bolt", "steel"), ("nut", "brass")] {
+            parts.insert_item(Arc::new(Part { name: name.into(), material: material.into() }));
+        }
+
+        let material = Reference::new("material", (221, 2201), (220, 2200), |item| {
+            item.downcast_ref::<Part>().map(|p| p.material.clone())
+        });
+        assert!(material.connects(&parts, &materials));
+        assert_eq!(material.dangling(&parts, &materials), Some(("nut".to_string(), "brass".to_string())));
+
+        // 같은 이름의 인덱스가 있으면 문자열 키로 인덱스 조회
+        let names = |cursors: Vec<&Cursor>| cursors.iter().filter_map(|c| c.key_as::<String>()).collect::<Vec<_>>();
+        assert_eq!(names(material.referrers(&parts, &"steel".to_string())), ["bolt"]);
+        parts.create_index("material", Arc::new(|item| item.downcast_ref::<Part>().map(|p| IndexValue::from(p.material.clone()))));
+        assert_eq!(names(material.referrers(&parts, &"steel".to_string())), ["bolt"]);
+
+        parts.remove("nut".to_string());
+        assert_eq!(material.dangling(&parts, &materials), None);
+
+        // 아이템 타입이 다른 테이블은 참조 대상이 아님
+        let mut session = new_session();
+        let wrong = Reference::new("wrong", (172, 1701), (170, 1700), |item| Some(item.key()));
+        assert!(!session.add_reference(wrong));
+        assert!(!Reference::<String>::new("swapped", (220, 2200), (221, 2201), |_| None).connects(&parts, &materials));
+    }
+}
@@ -0,0 +1,303 @@
+use std::fmt;
+use std::sync::Arc;
+
+use crate::guid::Guid;
+use crate::index::IndexValue;
+use crate::item::DItem;
+
+/// 필드 자료형
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum FieldType {
+    Bool,
+    Int,
+    Float,
+    Str,
+    Guid,
+    Bytes,
+}
+
+/// 필드 값
+#[derive(Clone, Debug, PartialEq)]
+pub enum FieldValue {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Str(String),
+    Guid(Guid),
+    Bytes(Vec<u8>),
+}
+
+impl FieldValue {
+    pub fn field_type(&self) -> FieldType {
+        match self {
+            FieldValue::Bool(_) => FieldType::Bool,
+            FieldValue::Int(_) => FieldType::Int,
+            FieldValue::Float(_) => FieldType::Float,
+            FieldValue::Str(_) => FieldType::Str,
+            FieldValue::Guid(_) => FieldType::Guid,
+            FieldValue::Bytes(_) => FieldType::Bytes,
+        }
+    }
+
+    /// 인덱스/조회용 값 (순서가 없는 Float, Bytes 는 None)
+    pub fn to_index_value(&self) -> Option<IndexValue> {
+        match self {
+            FieldValue::Bool(v) => Some(IndexValue::Bool(*v)),
+            FieldValue::Int(v) => Some(IndexValue::Int(*v)),
+            FieldValue::Str(v) => Some(IndexValue::Str(v.clone())),
+            FieldValue::Guid(v) => Some(IndexValue::Str(v.to_string())),
+            FieldValue::Float(_) | FieldValue::Bytes(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for FieldValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FieldValue::Bool(v) => write!(f, "{v}"),
+            FieldValue::Int(v) => write!(f, "{v}"),
+            FieldValue::Float(v) => write!(f, "{v}"),
+            FieldValue::Str(v) => write!(f, "{v:?}"),
+            FieldValue::Guid(v) => write!(f, "{v}"),
+            FieldValue::Bytes(v) => write!(f, "{} bytes", v.len()),
+        }
+    }
+}
+
+impl From<bool> for FieldValue {
+    fn from(v: bool) -> Self {
+        FieldValue::Bool(v)
+    }
+}
+
+impl From<i32> for FieldValue {
+    fn from(v: i32) -> Self {
+        FieldValue::Int(v as i64)
+    }
+}
+
+impl From<i64> for FieldValue {
+    fn from(v: i64) -> Self {
+        FieldValue::Int(v)
+    }
+}
+
+impl From<f64> for FieldValue {
+    fn from(v: f64) -> Self {
+        FieldValue::Float(v)
+    }
+}
+
+impl From<&str> for FieldValue {
+    fn from(v: &str) -> Self {
+        FieldValue::Str(v.to_string())
+    }
+}
+
+impl From<String> for FieldValue {
+    fn from(v: String) -> Self {
+        FieldValue::Str(v)
+    }
+}
+
+impl From<Guid> for FieldValue {
+    fn from(v: Guid) -> Self {
+        FieldValue::Guid(v)
+    }
+}
+
+impl From<Vec<u8>> for FieldValue {
+    fn from(v: Vec<u8>) -> Self {
+        FieldValue::Bytes(v)
+    }
+}
+
+/// FieldValue 로 주고받을 수 있는 필드 자료형 (derive 가 스키마를 만들 때 사용)
+pub trait ReflectValue: Sized {
+    const FIELD_TYPE: FieldType;
+
+    fn to_field(&self) -> FieldValue;
+
+    /// 자료형이 맞지 않거나 범위를 넘으면 None
+    fn from_field(value: FieldValue) -> Option<Self>;
+}
+
+impl ReflectValue for bool {
+    const FIELD_TYPE: FieldType = FieldType::Bool;
+
+    fn to_field(&self) -> FieldValue {
+        FieldValue::Bool(*self)
+    }
+
+    fn from_field(value: FieldValue) -> Option<Self> {
+        match value {
+            FieldValue::Bool(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+impl ReflectValue for i32 {
+    const FIELD_TYPE: FieldType = FieldType::Int;
+
+    fn to_field(&self) -> FieldValue {
+        FieldValue::Int(*self as i64)
+    }
+
+    fn from_field(value: FieldValue) -> Option<Self> {
+        match value {
+            FieldValue::Int(v) => i32::try_from(v).ok(),
+            _ => None,
+        }
+    }
+}
+
+impl ReflectValue for i64 {
+    const FIELD_TYPE: FieldType = FieldType::Int;
+
+    fn to_field(&self) -> FieldValue {
+        FieldValue::Int(*self)
+    }
+
+    fn from_field(value: FieldValue) -> Option<Self> {
+        match value {
+            FieldValue::Int(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+impl ReflectValue for f64 {
+    const FIELD_TYPE: FieldType = FieldType::Float;
+
+    fn to_field(&self) -> FieldValue {
+        FieldValue::Float(*self)
+    }
+
+    fn from_field(value: FieldValue) -> Option<Self> {
+        match value {
+            FieldValue::Float(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+impl ReflectValue for String {
+    const FIELD_TYPE: FieldType = FieldType::Str;
+
+    fn to_field(&self) -> FieldValue {
+        FieldValue::Str(self.clone())
+    }
+
+    fn from_field(value: FieldValue) -> Option<Self> {
+        match value {
+            FieldValue::Str(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+impl ReflectValue for Guid {
+    const FIELD_TYPE: FieldType = FieldType::Guid;
+
+    fn to_field(&self) -> FieldValue {
+        FieldValue::Guid(self.clone())
+    }
+
+    fn from_field(value: FieldValue) -> Option<Self> {
+        match value {
+            FieldValue::Guid(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+impl ReflectValue for Vec<u8> {
+    const FIELD_TYPE: FieldType = FieldType::Bytes;
+
+    fn to_field(&self) -> FieldValue {
+        FieldValue::Bytes(self.clone())
+    }
+
+    fn from_field(value: FieldValue) -> Option<Self> {
+        match value {
+            FieldValue::Bytes(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+pub type FieldGetter = Arc<dyn Fn(&dyn DItem) -> Option<FieldValue> + Send + Sync>;
+
+/// 값을 바꾼 새 아이템을 만듦 (아이템은 불변이므로 Table::modify 로 교체)
+pub type FieldSetter = Arc<dyn Fn(&dyn DItem, FieldValue) -> Option<Arc<dyn DItem>> + Send + Sync>;
+
+/// 필드 하나의 정의
+#[derive(Clone)]
+pub struct FieldDef {
+    pub name: String,
+    pub field_type: FieldType,
+    pub get: FieldGetter,
+    pub set: Option<FieldSetter>, // None = 읽기 전용
+}
+
+/// 아이템 타입의 필드 목록 (TypeInfo 에 등록)
+#[derive(Clone, Default)]
+pub struct ItemSchema {
+    pub fields: Vec<FieldDef>,
+}
+
+impl ItemSchema {
+    pub fn new() -> Self {
+        ItemSchema { fields: Vec::new() }
+    }
+
+    /// 읽기/쓰기 필드 추가 (set 은 값이 맞지 않으면 None)
+    pub fn field<T: DItem>(
+        mut self,
+        name: &str,
+        field_type: FieldType,
+        get: impl Fn(&T) -> FieldValue + Send + Sync + 'static,
+        set: impl Fn(&T, FieldValue) -> Option<T> + Send + Sync + 'static,
+    ) -> Self {
+        let setter: FieldSetter = Arc::new(move |item, value| {
+            let item = item.downcast_ref::<T>()?;
+            set(item, value).map(|new_item| Arc::new(new_item) as Arc<dyn DItem>)
+        });
+        self.fields.push(FieldDef {
+            name: name.to_string(),
+            field_type,
+            get: Self::getter(get),
+            set: Some(setter),
+        });
+        self
+    }
+
+    /// 읽기 전용 필드 추가
+    pub fn read_only<T: DItem>(
+        mut self,
+        name: &str,
+        field_type: FieldType,
+        get: impl Fn(&T) -> FieldValue + Send + Sync + 'static,
+    ) -> Self {
+        self.fields.push(FieldDef {
+            name: name.to_string(),
+            field_type,
+            get: Self::getter(get),
+            set: None,
+        });
+        self
+    }
+
+    pub fn find(&self, name: &str) -> Option<&FieldDef> {
+        self.fields.iter().find(|f| f.name == name)
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        self.fields.iter().map(|f| f.name.as_str()).collect()
+    }
+
+    fn getter<T: DItem>(get: impl Fn(&T) -> FieldValue + Send + Sync + 'static) -> FieldGetter {
+        Arc::new(move |item| item.downcast_ref::<T>().map(&get))
+    }
+}
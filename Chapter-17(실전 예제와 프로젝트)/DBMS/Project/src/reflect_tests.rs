@@ -0,0 +1,84 @@
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use crate::guid::Guid;
+    use crate::item::DItem;
+    use crate::item_factory::{item_factory, item_factory_mut};
+    use crate::query::Field;
+    use crate::reflect::{FieldType, FieldValue, ItemSchema};
+    use crate::session::Session;
+
+    #[derive(Clone, Debug)]
+    struct Part {
+        key: i32,
+        name: String,
+        weight: f64,
+        id: Guid,
+    }
+
+    impl DItem for Part {
+        fn key(&self) -> i32 { self.key }
+        fn item_type(&self) -> u16 { 1400 }
+        fn table_type(&self) -> u16 { 140 }
+        fn serialize(&self, _stream: &mut dyn crate::tx_stream::TxStream, _session: &Session) {}
+    }
+
+    fn new_session() -> Session {
+        let schema = ItemSchema::new()
+            .field("name", FieldType::Str, |p: &Part| p.name.clone().into(), |p, v| match v {
+                FieldValue::Str(name) => Some(Part { name, ..p.clone() }),
+                _ => None,
+            })
+            .field("weight", FieldType::Float, |p: &Part| p.weight.into(), |p, v| match v {
+                FieldValue::Float(weight) => Some(Part { weight, ..p.clone() }),
+                _ => None,
+            })
+            .read_only("id", FieldType::Guid, |p: &Part| p.id.clone().into());
+        let mut factory = item_factory_mut().lock().unwrap();
+        factory.register_type(
+            1400,
+            140,
+            Arc::new(|key| Arc::new(Part { key, name: String::new(), weight: 0.0, id: Guid::null() })),
+            Arc::new(|_item| {}),
+        );
+        assert!(factory.register_schema(1400, schema));
+        drop(factory);
+
+        let mut session = Session::new();
+        session.register_table(140, 1400);
+        session
+    }
+
+    #[test]
+    fn test_get_and_set_fields_by_name() {
+        let factory = item_factory();
+        let mut session = new_session();
+        let table = session.get_table_mut(140).unwrap();
+        table.insert(1, factory);
+        table.insert(2, factory);
+        session.commit_all().unwrap();
+
+        let table = session.get_table_mut(140).unwrap();
+        assert!(table.set_field(1, "name", "bolt".into(), factory).is_some());
+        assert!(table.set_field(1, "weight", 2.5.into(), factory).is_some());
+        // 자료형이 다르거나, 읽기 전용이거나, 없는 필드는 거부
+        assert!(table.set_field(1, "weight", "heavy".into(), factory).is_none());
+        assert!(table.set_field(1, "id", Guid::new().into(), factory).is_none());
+        assert!(table.set_field(1, "color", "red".into(), factory).is_none());
+        session.commit_all().unwrap();
+
+        let table = session.get_table(140).unwrap();
+        assert_eq!(table.get_field(1, "name", factory), Some(FieldValue::Str("bolt".into())));
+        let item = table.get(1).unwrap().data.clone();
+        let names: Vec<String> = factory.lock().unwrap().fields_of(item.as_ref()).into_iter().map(|(n, _)| n).collect();
+        assert_eq!(names, vec!["name", "weight", "id"]);
+
+        // 스키마 기반 조회
+        let name = Field::reflected(1400, "name").unwrap();
+        assert_eq!(session.query(140).where_eq(&name, "bolt").keys(), vec![1]);
+
+        // set_field 도 undo 대상
+        session.undo_all().unwrap();
+        assert_eq!(session.get_table(140).unwrap().get_field(1, "name", factory), Some(FieldValue::Str(String::new())));
+    }
+}
@@ -1,8 +1,40 @@
-use std::collections::HashMap;
-use crate::table::Table;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io;
+use std::path::Path;
+use std::sync::Arc;
+use crate::define::TxAction;
+use crate::guid::Guid;
+use crate::hashset::StorageMode;
+use crate::item::{Cursor, DItem};
+use crate::item_factory::item_factory;
+use crate::mvcc::{latest_version, next_version, PinRegistry, SnapshotView};
+use crate::query::Query;
+use crate::reference::{DeletePolicy, Reference, ReferenceError};
+use crate::sql::{SqlError, SqlExecutor, SqlResult};
+use crate::observer::{ChangeBatch, ChangeCallback, ChangeEvent, ChangeSource, ObserverList, Outbox, SubscriptionId};
+use crate::snapshot;
+use crate::table::{GuidRegistry, Table};
+use crate::tx_manager::{collect_branches, next_group_id, BranchInfo, HistoryEntry, HistoryLimit};
+use crate::tx_delta_list::TxDeltaList;
+use crate::wal::{WalEntry, WriteAheadLog};
 
 pub struct Session {
     pub tables: HashMap<u16, Table>, // key: table_type
+    wal: Option<WriteAheadLog>,
+    undo_tree: Option<SessionUndoTree>,
+    history_limit: HistoryLimit,
+    observers: ObserverList,
+    pins: PinRegistry, // 스냅샷 뷰가 고정한 버전
+    references: Vec<Reference>, // 커밋 시 검사하는 테이블 간 참조
+    guids: GuidRegistry, // 모든 테이블의 GUID → (table_type, 키)
+}
+
+/// 세션 단위 undo 트리: 커밋 그룹 사이의 부모 관계
+#[derive(Default)]
+struct SessionUndoTree {
+    parents: HashMap<u64, u64>,         // group → 부모 group (0 = 루트)
+    preferred_child: HashMap<u64, u64>, // redo 시 따라갈 자식
+    head: u64,                          // 현재 위치
 }
 
 
@@ -10,15 +42,93 @@ impl Session {
     pub fn new() -> Self {
         Session {
             tables: HashMap::new(),
+            wal: None,
+            undo_tree: None,
+            history_limit: HistoryLimit::default(),
+            observers: ObserverList::new(),
+            pins: PinRegistry::default(),
+            references: Vec::new(),
+            guids: GuidRegistry::default(),
+        }
+    }
+
+    /// 로그 파일로 세션 열기: 완료된 트랜잭션을 재생하고 이후 커밋은 같은 로그에 기록
+    pub fn open(path: &str) -> io::Result<Self> {
+        let (mut wal, records) = WriteAheadLog::open(path, item_factory())?;
+        let mut session = Session::new();
+        for record in &records {
+            session.apply_entries(record);
+        }
+        wal.upgrade(&records, &session)?;
+        session.wal = Some(wal);
+        Ok(session)
+    }
+
+    /// 스냅샷과 로그로 세션 열기: 스냅샷(있으면)을 읽은 뒤 로그를 이어서 재생
+    pub fn open_with_snapshot(snapshot_path: &str, log_path: &str) -> io::Result<Self> {
+        let mut session = if Path::new(snapshot_path).exists() {
+            Session::load_snapshot(snapshot_path)?
+        } else {
+            Session::new()
+        };
+
+        let (mut wal, records) = WriteAheadLog::open(log_path, item_factory())?;
+        for record in &records {
+            session.apply_entries(record);
+        }
+        wal.upgrade(&records, &session)?;
+        session.wal = Some(wal);
+        Ok(session)
+    }
+
+    /// 전체 세션을 스냅샷 파일로 저장 (복원 함수가 없는 타입의 아이템이 있으면 실패)
+    pub fn save_snapshot(&self, path: &str) -> io::Result<()> {
+        {
+            let factory = item_factory().lock().map_err(|_| io::Error::other("item factory lock poisoned"))?;
+            for table in self.tables.values().filter(|t| t.items.count() > 0) {
+                factory.check_restorable(table.item_type)?;
+            }
+        }
+        snapshot::write_snapshot(self, path)
+    }
+
+    /// 스냅샷 파일에서 세션 복원
+    pub fn load_snapshot(path: &str) -> io::Result<Self> {
+        let mut session = snapshot::read_snapshot(path, item_factory())?;
+        let version = next_version();
+        for table in session.tables.values_mut() {
+            table.sync_versions(version);
         }
+        Ok(session)
+    }
+
+    /// 체크포인트: 스냅샷 저장 후 로그를 비움 (커밋되지 않은 변경이 있으면 실패)
+    pub fn checkpoint(&mut self, snapshot_path: &str) -> io::Result<()> {
+        if self.tables.values().any(|t| t.tx.current_count() > 0) {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "uncommitted changes"));
+        }
+        self.save_snapshot(snapshot_path)?;
+        if let Some(wal) = self.wal.as_mut() {
+            wal.reset()?;
+        }
+        Ok(())
     }
 
     /// 테이블 등록
     pub fn register_table(&mut self, table_type: u16, item_type: u16) -> bool {
+        self.register_table_with(table_type, item_type, StorageMode::Hash)
+    }
+
+    /// 저장 방식을 지정해 테이블 등록
+    pub fn register_table_with(&mut self, table_type: u16, item_type: u16, mode: StorageMode) -> bool {
         if self.tables.contains_key(&table_type) {
             return false;
         }
-        let table = Table::new(table_type, item_type);
+        let mut table = Table::with_storage(table_type, item_type, mode);
+        table.tx.set_tree_mode(self.undo_tree.is_some());
+        table.set_pin_registry(self.pins.clone());
+        table.set_guid_registry(self.guids.clone());
+        table.set_outbox(self.observers.outbox());
         self.tables.insert(table_type, table);
         true
     }
@@ -33,19 +143,381 @@ impl Session {
     }
 }
 
+impl Default for Session {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 
 impl Session {
-    /// 전체 undo
-    pub fn undo_all(&mut self) {
+    /// 전체 커밋: 변경된 테이블의 현재 델타를 로그에 기록한 뒤 하나의 그룹으로 undo 스택에 이동
+    pub fn commit_all(&mut self) -> io::Result<()> {
+        self.commit_labelled("")
+    }
+
+    /// 설명을 붙여 전체 커밋 (history 에 표시)
+    /// 참조 무결성을 위반하면 커밋하지 않고 ReferenceError 를 담은 오류 반환 (변경은 그대로 남음)
+    pub fn commit_labelled(&mut self, label: &str) -> io::Result<()> {
+        self.commit_locking(label, &|_, _| true)
+    }
+
+    /// 연쇄 삭제/참조 비움으로 바꿀 아이템마다 lock(table_type, key) 로 배타 잠금을 얻은 뒤 커밋
+    /// (잠금을 얻지 못하면 ReferenceError::Locked, SharedTransaction 이 사용)
+    pub(crate) fn commit_locking(&mut self, label: &str, lock: &dyn Fn(u16, i32) -> bool) -> io::Result<()> {
+        self.enforce_references(lock)?;
+
+        let mut entries = Vec::new();
+        for table_type in self.sorted_table_types() {
+            let table = &self.tables[&table_type];
+            Self::collect_entries(table, table.tx.current(), &mut entries);
+        }
+        if entries.is_empty() {
+            return Ok(());
+        }
+        self.write_log(&entries)?;
+
+        let group = next_group_id();
+        let version = next_version();
         for table in self.tables.values_mut() {
-            table.undo();
+            table.commit_group(group, label, version);
+            table.tx.clear_redo(); // 새 커밋 이후에는 세션 전체의 redo 가 무효
+        }
+        self.notify(ChangeSource::Commit, group, label, &entries);
+        if let Some(tree) = self.undo_tree.as_mut() {
+            tree.parents.insert(group, tree.head);
+            tree.preferred_child.insert(tree.head, group);
+            tree.head = group;
         }
+        self.trim_history();
+        Ok(())
     }
 
-    /// 전체 redo
-    pub fn redo_all(&mut self) {
+    /// 기록에서 버려진 아이템 해제 (버린 것이 있을 때만 ItemFactory 를 잠금)
+    fn release_items(&mut self) {
+        if !self.tables.values().any(|t| t.tx.has_released()) {
+            return;
+        }
+        let Ok(factory) = item_factory().lock() else {
+            return;
+        };
         for table in self.tables.values_mut() {
-            table.redo();
+            table.release_items(&factory);
+        }
+    }
+
+    /// 모든 테이블의 커밋되지 않은 델타 (세이브포인트용)
+    pub fn pending_state(&self) -> HashMap<u16, TxDeltaList> {
+        self.tables
+            .iter()
+            .map(|(table_type, table)| (*table_type, table.tx.current().clone()))
+            .collect()
+    }
+
+    /// 커밋되지 않은 변경을 저장된 시점으로 되돌림 (빈 맵이면 전부 취소)
+    pub fn rollback_pending_to(&mut self, saved: &HashMap<u16, TxDeltaList>) {
+        let empty = TxDeltaList::new();
+        for (table_type, table) in self.tables.iter_mut() {
+            table.rollback_current_to(saved.get(table_type).unwrap_or(&empty));
+        }
+    }
+
+    /// 전체 undo: 마지막 커밋 그룹에 속한 모든 테이블을 커밋 역순으로 함께 되돌림
+    pub fn undo_all(&mut self) -> io::Result<()> {
+        if let Some(tree) = self.undo_tree.as_mut() {
+            if tree.head == 0 {
+                return Ok(());
+            }
+            let head = tree.head;
+            let parent = tree.parents[&head];
+            tree.preferred_child.insert(parent, head);
+            return self.goto(parent).map(|_| ());
+        }
+
+        let Some(group) = self.tables.values().filter_map(|t| t.tx.undo_group()).max() else {
+            return Ok(());
+        };
+
+        let mut entries = Vec::new();
+        let mut label = String::new();
+        for table_type in self.sorted_table_types().into_iter().rev() {
+            let table = self.tables.get_mut(&table_type).unwrap();
+            if table.tx.undo_group() != Some(group) {
+                continue;
+            }
+            if let Some(delta) = table.undo() {
+                Self::collect_entries(table, &delta.inverse(), &mut entries);
+                label = delta.label;
+            }
+        }
+        self.write_log(&entries)?;
+        self.notify(ChangeSource::Undo, group, &label, &entries);
+        Ok(())
+    }
+
+    /// 전체 redo: 마지막으로 되돌린 그룹을 커밋 순서대로 다시 적용
+    pub fn redo_all(&mut self) -> io::Result<()> {
+        if let Some(tree) = self.undo_tree.as_ref() {
+            return match tree.preferred_child.get(&tree.head) {
+                Some(&child) => self.goto(child).map(|_| ()),
+                None => Ok(()),
+            };
+        }
+
+        let Some(group) = self.tables.values().filter_map(|t| t.tx.redo_group()).min() else {
+            return Ok(());
+        };
+        // 이후에 다른 커밋이 있었다면 redo 경로가 끊긴 것
+        if self.tables.values().filter_map(|t| t.tx.undo_group()).any(|g| g > group) {
+            return Ok(());
+        }
+
+        let mut entries = Vec::new();
+        let mut label = String::new();
+        for table_type in self.sorted_table_types() {
+            let table = self.tables.get_mut(&table_type).unwrap();
+            if table.tx.redo_group() != Some(group) {
+                continue;
+            }
+            if let Some(delta) = table.redo() {
+                Self::collect_entries(table, &delta, &mut entries);
+                label = delta.label;
+            }
+        }
+        self.write_log(&entries)?;
+        self.notify(ChangeSource::Redo, group, &label, &entries);
+        Ok(())
+    }
+
+    /// 세션 전체 커밋 기록: 그룹 단위로 합쳐 오래된 순으로 반환
+    pub fn history(&self) -> Vec<HistoryEntry> {
+        let mut groups: BTreeMap<u64, HistoryEntry> = BTreeMap::new();
+        for table in self.tables.values() {
+            for entry in table.tx.history() {
+                groups
+                    .entry(entry.group)
+                    .and_modify(|e| e.action_count += entry.action_count)
+                    .or_insert(entry);
+            }
+        }
+        groups.into_values().collect()
+    }
+
+    /// 현재 위치: 적용된 커밋 그룹 수
+    pub fn history_position(&self) -> usize {
+        self.history().iter().filter(|e| e.applied).count()
+    }
+
+    /// 적용된 그룹이 position 개가 될 때까지 undo (되돌린 그룹 수 반환)
+    pub fn undo_to(&mut self, position: usize) -> io::Result<usize> {
+        let mut count = 0;
+        while self.history_position() > position && self.has_undo() {
+            self.undo_all()?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// 적용된 그룹이 position 개가 될 때까지 redo (다시 적용한 그룹 수 반환)
+    pub fn redo_to(&mut self, position: usize) -> io::Result<usize> {
+        let mut count = 0;
+        while self.history_position() < position {
+            let before = self.history_position();
+            self.redo_all()?;
+            if self.history_position() == before {
+                break;
+            }
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// undo 트리 모드 켜기: 이후 undo 뒤의 커밋은 redo 경로를 지우지 않고 새 가지를 만듦
+    /// 지금까지의 기록은 한 줄짜리 가지가 됨
+    pub fn enable_undo_tree(&mut self) {
+        if self.undo_tree.is_some() {
+            return;
+        }
+        let mut tree = SessionUndoTree::default();
+        let mut parent = 0;
+        for entry in self.history() {
+            tree.parents.insert(entry.group, parent);
+            tree.preferred_child.insert(parent, entry.group);
+            if entry.applied {
+                tree.head = entry.group;
+            }
+            parent = entry.group;
+        }
+        for table in self.tables.values_mut() {
+            table.tx.set_tree_mode(true);
+        }
+        self.undo_tree = Some(tree);
+    }
+
+    pub fn is_undo_tree(&self) -> bool {
+        self.undo_tree.is_some()
+    }
+
+    /// undo 트리의 현재 노드 (0 = 루트)
+    pub fn undo_tree_head(&self) -> Option<u64> {
+        self.undo_tree.as_ref().map(|t| t.head)
+    }
+
+    /// undo 트리의 가지 목록
+    pub fn branches(&self) -> Vec<BranchInfo> {
+        let Some(tree) = self.undo_tree.as_ref() else {
+            return Vec::new();
+        };
+        collect_branches(&tree.parents, tree.head, |group| self.group_label(group))
+    }
+
+    /// undo 트리의 임의 노드(커밋 그룹)로 이동, 가지 전환에도 사용 (0 = 루트)
+    pub fn goto(&mut self, group: u64) -> io::Result<bool> {
+        let Some(tree) = self.undo_tree.as_mut() else {
+            return Ok(false);
+        };
+        if group != 0 && !tree.parents.contains_key(&group) {
+            return Ok(false);
+        }
+
+        let mut path = Vec::new();
+        let mut node = group;
+        while node != 0 {
+            path.push(node);
+            node = tree.parents[&node];
+        }
+        path.reverse();
+
+        let mut parent = 0;
+        for &g in &path {
+            tree.preferred_child.insert(parent, g);
+            parent = g;
+        }
+        let previous = tree.head;
+        tree.head = group;
+
+        // 세션이 redo 할 경로 (history 표시용으로 테이블에도 맞춰 둠)
+        let mut future = Vec::new();
+        let mut node = group;
+        while let Some(&child) = tree.preferred_child.get(&node) {
+            future.push(child);
+            node = child;
+        }
+
+        let mut undone = Vec::new();
+        let mut redone = Vec::new();
+        for table_type in self.sorted_table_types() {
+            let table = self.tables.get_mut(&table_type).unwrap();
+            let target = path.iter().rev().copied().find(|g| table.tx.node(*g).is_some()).unwrap_or(0);
+            if let Some((table_undone, table_redone)) = table.goto(target) {
+                for delta in &table_undone {
+                    Self::collect_entries(table, delta, &mut undone);
+                }
+                for delta in &table_redone {
+                    Self::collect_entries(table, delta, &mut redone);
+                }
+            }
+            let table_future: Vec<u64> = future.iter().copied().filter(|g| table.tx.node(*g).is_some()).collect();
+            table.tx.set_redo_path(&table_future);
+        }
+
+        let mut entries = undone.clone();
+        entries.extend(redone.iter().cloned());
+        self.write_log(&entries)?;
+        self.notify(ChangeSource::Undo, previous, &self.group_label(previous), &undone);
+        self.notify(ChangeSource::Redo, group, &self.group_label(group), &redone);
+        Ok(true)
+    }
+
+    fn group_label(&self, group: u64) -> String {
+        self.tables
+            .values()
+            .find_map(|t| t.tx.node(group).map(|n| n.delta.label.clone()))
+            .unwrap_or_default()
+    }
+
+    /// 세션 기록 한도 설정: 커밋 그룹 단위로 오래된 것부터 버림
+    pub fn set_history_limit(&mut self, limit: HistoryLimit) {
+        self.history_limit = limit;
+        self.trim_history();
+    }
+
+    pub fn history_limit(&self) -> HistoryLimit {
+        self.history_limit
+    }
+
+    /// 모든 테이블 기록의 대략적인 메모리 사용량 (바이트)
+    pub fn history_memory_usage(&self) -> usize {
+        self.tables.values().map(|t| t.tx.memory_usage()).sum()
+    }
+
+    /// 보관 중인 커밋 그룹 수
+    pub fn history_step_count(&self) -> usize {
+        match self.undo_tree.as_ref() {
+            Some(tree) => tree.parents.len(),
+            None => self.history().len(),
+        }
+    }
+
+    /// 한도를 넘으면 가장 오래된 커밋 그룹부터 모든 테이블에서 함께 버림 (버린 그룹 수 반환)
+    pub fn trim_history(&mut self) -> usize {
+        let mut removed = 0;
+        while self.history_limit.is_exceeded(self.history_step_count(), self.history_memory_usage()) {
+            let oldest = match self.undo_tree.as_ref() {
+                Some(tree) => {
+                    let mut node = tree.head;
+                    while node != 0 && tree.parents[&node] != 0 {
+                        node = tree.parents[&node];
+                    }
+                    Some(node).filter(|g| *g != 0)
+                }
+                None => self.history().iter().find(|e| e.applied).map(|e| e.group),
+            };
+            let Some(oldest) = oldest else {
+                break;
+            };
+
+            let mut groups = HashSet::from([oldest]);
+            if let Some(tree) = self.undo_tree.as_mut() {
+                // 루트에서 갈라진 다른 가지는 더 이상 도달할 수 없음
+                let mut stack: Vec<u64> = tree.parents.iter().filter(|(g, p)| **p == 0 && **g != oldest).map(|(g, _)| *g).collect();
+                while let Some(group) = stack.pop() {
+                    groups.insert(group);
+                    stack.extend(tree.parents.iter().filter(|(_, p)| **p == group).map(|(g, _)| *g));
+                }
+                tree.parents.retain(|g, _| !groups.contains(g));
+                for parent in tree.parents.values_mut() {
+                    if groups.contains(parent) {
+                        *parent = 0;
+                    }
+                }
+                let next = tree.preferred_child.get(&oldest).copied();
+                tree.preferred_child.retain(|p, c| !groups.contains(p) && !groups.contains(c));
+                if let Some(next) = next.filter(|g| !groups.contains(g)) {
+                    tree.preferred_child.insert(0, next);
+                }
+            }
+
+            for table in self.tables.values_mut() {
+                table.tx.discard_groups(&groups);
+            }
+            removed += groups.len();
+        }
+        self.release_items(); // 커밋으로 사라진 redo 경로 포함
+        removed
+    }
+
+    pub fn has_undo(&self) -> bool {
+        match self.undo_tree.as_ref() {
+            Some(tree) => tree.head != 0,
+            None => self.tables.values().any(|t| t.tx.has_undo()),
+        }
+    }
+
+    pub fn has_redo(&self) -> bool {
+        match self.undo_tree.as_ref() {
+            Some(tree) => tree.preferred_child.contains_key(&tree.head),
+            None => self.tables.values().any(|t| t.tx.has_redo()),
         }
     }
 
@@ -55,6 +527,243 @@ impl Session {
             table.clear();
         }
     }
+
+    /// 로그에서 읽은 트랜잭션을 테이블에 적용 (필요하면 테이블 자동 등록)
+    fn apply_entries(&mut self, entries: &[WalEntry]) {
+        let version = next_version();
+        for entry in entries {
+            self.register_table(entry.table_type, entry.item_type);
+            if let Some(table) = self.tables.get_mut(&entry.table_type) {
+                table.apply_action(&entry.action);
+                if let Some(key) = entry.action.key() {
+                    table.record_versions(&[key], version);
+                }
+            }
+        }
+    }
+
+    /// GUID 로 아이템 찾기: (테이블, 커서). undo/redo 와 로그/스냅샷 복원 후에도 유지
+    pub fn find_by_guid(&self, guid: &Guid) -> Option<(&Table, &Cursor)> {
+        let (table_type, key) = *self.guids.read().unwrap().get(guid)?;
+        let table = self.tables.get(&table_type)?;
+        Some((table, table.get(key)?))
+    }
+
+    /// 테이블 간 참조 선언 (두 테이블이 참조의 아이템 타입으로 등록되어 있고 이름이 겹치지 않아야 함)
+    pub fn add_reference(&mut self, reference: Reference) -> bool {
+        let (Some(from), Some(to)) = (self.tables.get(&reference.from_table), self.tables.get(&reference.to_table)) else {
+            return false;
+        };
+        if !reference.connects(from, to) || self.references.iter().any(|r| r.name == reference.name) {
+            return false;
+        }
+        self.references.push(reference);
+        true
+    }
+
+    pub fn remove_reference(&mut self, name: &str) -> bool {
+        let before = self.references.len();
+        self.references.retain(|r| r.name != name);
+        self.references.len() != before
+    }
+
+    pub fn references(&self) -> &[Reference] {
+        &self.references
+    }
+
+    /// 커밋 전 참조 검사: 삭제된 대상에 정책을 적용(연쇄 삭제/참조 비움은 같은 커밋에 포함)한 뒤
+    /// 이번에 바뀐 아이템이 없는 대상을 가리키는지 확인. 위반 시 정책으로 바꾼 것은 되돌림
+    fn enforce_references(&mut self, lock: &dyn Fn(u16, i32) -> bool) -> Result<(), ReferenceError> {
+        if self.references.is_empty() {
+            return Ok(());
+        }
+        let saved = self.pending_state();
+        let result = self.apply_delete_policies(lock).and_then(|_| self.check_dangling());
+        if result.is_err() {
+            self.rollback_pending_to(&saved);
+        }
+        result
+    }
+
+    fn apply_delete_policies(&mut self, lock: &dyn Fn(u16, i32) -> bool) -> Result<(), ReferenceError> {
+        let references = self.references.clone();
+        let mut handled = HashSet::new();
+        loop {
+            // 이번 커밋에서 사라진 키 (연쇄 삭제로 새로 생기면 다시 반복)
+            let removed: Vec<(u16, i32)> = self
+                .tables
+                .iter()
+                .flat_map(|(table_type, table)| {
+                    table.tx.current().keys.iter().filter(|key| table.get(**key).is_none()).map(|key| (*table_type, *key))
+                })
+                .filter(|removed| !handled.contains(removed))
+                .collect();
+            if removed.is_empty() {
+                return Ok(());
+            }
+
+            for (table_type, key) in removed {
+                handled.insert((table_type, key));
+                for reference in references.iter().filter(|r| r.to_table == table_type) {
+                    for (referrer_key, item) in self.referrers(reference, key) {
+                        if reference.on_delete != DeletePolicy::Restrict && !lock(reference.from_table, referrer_key) {
+                            return Err(ReferenceError::Locked {
+                                reference: reference.name.clone(),
+                                table_type,
+                                key,
+                                referrer_table: reference.from_table,
+                                referrer_key,
+                            });
+                        }
+                        let from = self.tables.get_mut(&reference.from_table).unwrap();
+                        let applied = match reference.on_delete {
+                            DeletePolicy::Restrict => false,
+                            DeletePolicy::Cascade => from.remove(referrer_key),
+                            DeletePolicy::SetNull => reference
+                                .cleared(item.as_ref())
+                                .is_some_and(|cleared| from.modify(referrer_key, cleared).is_some()),
+                        };
+                        if !applied {
+                            return Err(ReferenceError::Restricted {
+                                reference: reference.name.clone(),
+                                table_type,
+                                key,
+                                referrer_table: reference.from_table,
+                                referrer_key,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// 대상 키를 가리키는 아이템 (같은 이름의 인덱스가 있으면 사용)
+    fn referrers(&self, reference: &Reference, target: i32) -> Vec<(i32, Arc<dyn DItem>)> {
+        let Some(from) = self.tables.get(&reference.from_table) else {
+            return Vec::new();
+        };
+        reference.referrers(from, &target).into_iter().map(|c| (c.key(), c.data.clone())).collect()
+    }
+
+    fn check_dangling(&self) -> Result<(), ReferenceError> {
+        for reference in &self.references {
+            let (Some(from), Some(to)) = (self.tables.get(&reference.from_table), self.tables.get(&reference.to_table)) else {
+                continue;
+            };
+            if let Some((key, target_key)) = reference.dangling(from, to) {
+                return Err(ReferenceError::Dangling {
+                    reference: reference.name.clone(),
+                    table_type: reference.from_table,
+                    key,
+                    target_table: reference.to_table,
+                    target_key,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// 테이블 조회 시작 (조건, 정렬, 집계를 이어 붙임)
+    pub fn query(&self, table_type: u16) -> Query<'_> {
+        Query::new(self, table_type)
+    }
+
+    /// 문장 실행 (SELECT/INSERT/DELETE, BEGIN/COMMIT/ROLLBACK, UNDO/REDO)
+    pub fn execute(&mut self, source: &str) -> Result<Vec<SqlResult>, SqlError> {
+        SqlExecutor::new(self).execute(source)
+    }
+
+    /// 마지막으로 커밋된 버전에 고정된 읽기 전용 뷰
+    /// (뷰를 가진 동안에도 세션은 계속 수정할 수 있고, 뷰는 커밋되지 않은 변경을 보지 않음)
+    pub fn snapshot(&self) -> SnapshotView {
+        let tables = self.tables.iter().map(|(table_type, table)| (*table_type, table.versions())).collect();
+        SnapshotView::new(latest_version(), tables, self.pins.clone())
+    }
+
+    /// 열려 있는 스냅샷 뷰 수
+    pub fn snapshot_count(&self) -> usize {
+        self.pins.count()
+    }
+
+    /// 보관 중인 커밋 버전 수 (스냅샷이 없으면 키마다 하나)
+    pub fn version_count(&self) -> usize {
+        self.tables.values().map(|t| t.versions().read().unwrap().version_count()).sum()
+    }
+
+    /// 변경 구독: 세션 커밋, undo, redo 마다 한 묶음씩 전달
+    pub fn subscribe(&mut self, callback: ChangeCallback) -> SubscriptionId {
+        self.observers.subscribe(None, callback)
+    }
+
+    /// 특정 테이블의 변경만 구독
+    pub fn subscribe_table(&mut self, table_type: u16, callback: ChangeCallback) -> SubscriptionId {
+        self.observers.subscribe(Some(table_type), callback)
+    }
+
+    /// 구독 해지 (테이블에 직접 등록한 구독도 함께 찾음)
+    pub fn unsubscribe(&mut self, id: SubscriptionId) -> bool {
+        self.observers.unsubscribe(id) || self.tables.values_mut().any(|t| t.unsubscribe(id))
+    }
+
+    /// 세션과 모든 테이블의 알림을 outbox 에 모으도록 설정 (None 이면 바로 호출)
+    pub fn set_outbox(&mut self, outbox: Option<Outbox>) {
+        for table in self.tables.values_mut() {
+            table.set_outbox(outbox.clone());
+        }
+        self.observers.set_outbox(outbox);
+    }
+
+    fn notify(&self, source: ChangeSource, group: u64, label: &str, entries: &[WalEntry]) {
+        if self.observers.is_empty() {
+            return;
+        }
+        let mut batch = ChangeBatch::new(source, group, label);
+        batch.events = entries
+            .iter()
+            .filter_map(|e| ChangeEvent::from_action(e.table_type, &e.action))
+            .collect();
+        self.observers.notify(&batch);
+    }
+
+    fn collect_entries(table: &Table, delta: &TxDeltaList, entries: &mut Vec<WalEntry>) {
+        for action in delta.iter() {
+            entries.push(WalEntry {
+                table_type: table.table_type,
+                item_type: table.item_type,
+                action: action.clone(),
+            });
+        }
+    }
+
+    fn write_log(&mut self, entries: &[WalEntry]) -> io::Result<()> {
+        if entries.is_empty() {
+            return Ok(());
+        }
+        if self.wal.is_none() {
+            return Ok(());
+        }
+        // 다시 읽을 수 없는 레코드는 기록하지 않음 (커밋 실패, 변경은 그대로 남음)
+        {
+            let factory = item_factory().lock().map_err(|_| io::Error::other("item factory lock poisoned"))?;
+            for entry in entries.iter().filter(|e| !matches!(e.action, TxAction::Cancelled)) {
+                factory.check_restorable(entry.item_type)?;
+            }
+        }
+        // 페이로드 직렬화에 세션이 필요하므로 잠시 꺼내서 기록
+        let Some(mut wal) = self.wal.take() else {
+            return Ok(());
+        };
+        let result = wal.append(entries, self);
+        self.wal = Some(wal);
+        result
+    }
+
+    fn sorted_table_types(&self) -> Vec<u16> {
+        let mut types = self.table_types();
+        types.sort_unstable();
+        types
+    }
 }
 
 
@@ -0,0 +1,265 @@
+use std::collections::HashMap;
+use std::io;
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, TryLockError};
+use std::time::Duration;
+
+use crate::item::{Cursor, DItem};
+use crate::item_factory::item_factory;
+use crate::lock_manager::{LockError, LockManager, LockMode};
+use crate::mvcc::SnapshotView;
+use crate::observer::Outbox;
+use crate::session::Session;
+use crate::table::Table;
+use crate::tx_delta_list::TxDeltaList;
+
+const DEFAULT_LOCK_TIMEOUT: Duration = Duration::from_secs(5);
+
+struct SharedInner {
+    session: Mutex<Session>,
+    locks: LockManager,
+    lock_timeout: Duration,
+    next_tx: AtomicU64,
+    outbox: Outbox,         // 세션 잠금 안에서 생긴 알림
+    delivering: Mutex<()>, // 알림을 한 번에 한 곳에서만, 모인 순서대로 전달
+}
+
+/// 여러 스레드가 함께 쓰는 세션 (clone 하면 같은 세션을 가리킴)
+#[derive(Clone)]
+pub struct SharedSession {
+    inner: Arc<SharedInner>,
+}
+
+impl SharedSession {
+    pub fn new(session: Session) -> Self {
+        Self::with_lock_timeout(session, DEFAULT_LOCK_TIMEOUT)
+    }
+
+    /// 잠금 대기 시간을 지정해 생성
+    pub fn with_lock_timeout(mut session: Session, lock_timeout: Duration) -> Self {
+        let outbox = Outbox::default();
+        session.set_outbox(Some(outbox.clone()));
+        SharedSession {
+            inner: Arc::new(SharedInner {
+                session: Mutex::new(session),
+                locks: LockManager::new(),
+                lock_timeout,
+                next_tx: AtomicU64::new(1),
+                outbox,
+                delivering: Mutex::new(()),
+            }),
+        }
+    }
+
+    /// 트랜잭션 시작
+    pub fn begin(&self) -> SharedTransaction {
+        SharedTransaction {
+            shared: self.clone(),
+            id: self.inner.next_tx.fetch_add(1, Ordering::Relaxed),
+            pending: HashMap::new(),
+            lock_timeout: self.inner.lock_timeout,
+            finished: false,
+        }
+    }
+
+    /// 세션 직접 사용 (키 잠금을 거치지 않으므로 undo/redo, 스냅샷 같은 관리 작업용)
+    /// 변경 알림은 돌려준 잠금을 놓은 뒤에 전달되므로 구독자가 이 세션을 다시 잠가도 됨
+    pub fn session(&self) -> SessionGuard<'_> {
+        SessionGuard {
+            shared: self,
+            guard: Some(self.lock_session()),
+        }
+    }
+
+    fn lock_session(&self) -> MutexGuard<'_, Session> {
+        self.inner.session.lock().unwrap()
+    }
+
+    /// 모아 둔 알림 전달 (세션 잠금 밖에서 호출)
+    fn deliver(&self) {
+        loop {
+            // 다른 스레드나 바깥 호출 (콜백 안에서 다시 불린 경우) 이 전달 중이면 그쪽이 마저 전달
+            let turn = match self.inner.delivering.try_lock() {
+                Ok(turn) => turn,
+                Err(TryLockError::Poisoned(e)) => e.into_inner(),
+                Err(TryLockError::WouldBlock) => return,
+            };
+            loop {
+                let pending = std::mem::take(&mut *self.inner.outbox.lock().unwrap());
+                if pending.is_empty() {
+                    break;
+                }
+                for (callback, batch) in pending {
+                    callback(&batch);
+                }
+            }
+            drop(turn);
+            // 차례를 놓는 사이에 들어온 알림이 있으면 다시 전달
+            if self.inner.outbox.lock().unwrap().is_empty() {
+                return;
+            }
+        }
+    }
+
+    /// 커밋된 버전에 고정된 읽기 전용 뷰 (잠금 없이 읽으므로 쓰기를 막지 않음)
+    pub fn snapshot(&self) -> SnapshotView {
+        self.lock_session().snapshot()
+    }
+
+    /// 다른 핸들이 없으면 세션을 꺼냄 (이후 알림은 바로 호출)
+    pub fn into_session(self) -> Option<Session> {
+        self.deliver();
+        let inner = Arc::try_unwrap(self.inner).ok()?;
+        let mut session = inner.session.into_inner().ok()?;
+        session.set_outbox(None);
+        Some(session)
+    }
+}
+
+/// SharedSession::session 이 돌려주는 잠금: 놓을 때 그동안 생긴 알림을 잠금 밖에서 전달
+pub struct SessionGuard<'a> {
+    shared: &'a SharedSession,
+    guard: Option<MutexGuard<'a, Session>>,
+}
+
+impl Deref for SessionGuard<'_> {
+    type Target = Session;
+
+    fn deref(&self) -> &Session {
+        self.guard.as_ref().unwrap()
+    }
+}
+
+impl DerefMut for SessionGuard<'_> {
+    fn deref_mut(&mut self) -> &mut Session {
+        self.guard.as_mut().unwrap()
+    }
+}
+
+impl Drop for SessionGuard<'_> {
+    fn drop(&mut self) {
+        self.guard.take();
+        self.shared.deliver();
+    }
+}
+
+/// SharedSession 의 트랜잭션: 키 단위 잠금을 커밋/롤백까지 보유 (drop 시 자동 롤백)
+pub struct SharedTransaction {
+    shared: SharedSession,
+    id: u64,
+    pending: HashMap<u16, TxDeltaList>, // 이 트랜잭션의 커밋되지 않은 변경
+    lock_timeout: Duration,
+    finished: bool,
+}
+
+impl SharedTransaction {
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn set_lock_timeout(&mut self, timeout: Duration) {
+        self.lock_timeout = timeout;
+    }
+
+    /// 명시적 잠금 (이미 보유한 공유 잠금은 배타 잠금으로 업그레이드)
+    pub fn lock(&self, table_type: u16, key: i32, mode: LockMode) -> Result<(), LockError> {
+        self.shared.inner.locks.acquire(self.id, (table_type, key), mode, self.lock_timeout)
+    }
+
+    /// 공유 잠금 후 조회
+    pub fn get(&self, table_type: u16, key: i32) -> Result<Option<Cursor>, LockError> {
+        self.lock(table_type, key, LockMode::Shared)?;
+        let session = self.shared.lock_session();
+        Ok(session.get_table(table_type).and_then(|t| t.get(key).cloned()))
+    }
+
+    /// 배타 잠금 후 삽입
+    pub fn insert(&mut self, table_type: u16, key: i32) -> Result<Option<Cursor>, LockError> {
+        self.lock(table_type, key, LockMode::Exclusive)?;
+        Ok(self.edit(table_type, |table| table.insert(key, item_factory())).flatten())
+    }
+
+    /// 배타 잠금 후 수정
+    pub fn modify(&mut self, table_type: u16, key: i32, item: Arc<dyn DItem>) -> Result<Option<Cursor>, LockError> {
+        self.lock(table_type, key, LockMode::Exclusive)?;
+        Ok(self.edit(table_type, |table| table.modify(key, item)).flatten())
+    }
+
+    /// 배타 잠금 후 삭제
+    pub fn remove(&mut self, table_type: u16, key: i32) -> Result<bool, LockError> {
+        self.lock(table_type, key, LockMode::Exclusive)?;
+        Ok(self.edit(table_type, |table| table.remove(key)).unwrap_or(false))
+    }
+
+    /// 커밋: 이 트랜잭션의 변경만 하나의 그룹으로 로그와 undo 스택에 기록
+    pub fn commit(self) -> io::Result<()> {
+        self.commit_labelled("")
+    }
+
+    pub fn commit_labelled(mut self, label: &str) -> io::Result<()> {
+        self.finished = true;
+        let result = {
+            let mut session = self.shared.lock_session();
+            restore_pending(&mut self.pending, &mut session);
+            // 연쇄 삭제/참조 비움 대상도 배타 잠금 (세션을 잡은 채 기다리지 않고 바로 실패)
+            let locks = &self.shared.inner.locks;
+            let result = session.commit_locking(label, &|table_type, key| {
+                locks.acquire(self.id, (table_type, key), LockMode::Exclusive, Duration::ZERO).is_ok()
+            });
+            if result.is_err() {
+                session.rollback_pending_to(&HashMap::new()); // 기록에 실패하면 변경 취소
+            }
+            result
+        };
+        self.shared.inner.locks.release_all(self.id);
+        self.shared.deliver(); // 세션 잠금과 키 잠금을 모두 놓은 뒤 알림
+        result
+    }
+
+    /// 롤백: 이 트랜잭션의 변경만 되돌리고 잠금 해제
+    pub fn rollback(mut self) {
+        self.rollback_pending();
+    }
+
+    fn rollback_pending(&mut self) {
+        self.finished = true;
+        {
+            let mut session = self.shared.lock_session();
+            restore_pending(&mut self.pending, &mut session);
+            session.rollback_pending_to(&HashMap::new());
+        }
+        self.shared.inner.locks.release_all(self.id);
+    }
+
+    /// 테이블 변경 후, 세션의 현재 델타를 이 트랜잭션의 델타로 옮김
+    /// (다른 트랜잭션의 커밋에 섞이지 않도록 세션의 현재 델타는 항상 비워 둠)
+    fn edit<R>(&mut self, table_type: u16, f: impl FnOnce(&mut Table) -> R) -> Option<R> {
+        let mut session = self.shared.lock_session();
+        let table = session.get_table_mut(table_type)?;
+        let result = f(table);
+        let delta = table.tx.take_current();
+        let pending = self.pending.entry(table_type).or_default();
+        for action in delta.actions {
+            pending.add(action);
+        }
+        Some(result)
+    }
+}
+
+/// 보관한 델타를 세션의 현재 델타로 되돌려 놓음 (커밋/롤백 직전)
+fn restore_pending(pending: &mut HashMap<u16, TxDeltaList>, session: &mut Session) {
+    for (table_type, delta) in pending.drain() {
+        if let Some(table) = session.get_table_mut(table_type) {
+            table.tx.set_current(delta);
+        }
+    }
+}
+
+impl Drop for SharedTransaction {
+    fn drop(&mut self) {
+        if !self.finished {
+            self.rollback_pending(); // 자동 롤백
+        }
+    }
+}
@@ -0,0 +1,196 @@
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+    use std::time::Duration;
+    use crate::item::DItem;
+    use crate::item_factory::item_factory_mut;
+    use crate::lock_manager::{LockError, LockMode};
+    use crate::observer::ChangeSource;
+    use crate::reference::{Reference, ReferenceError};
+    use crate::session::Session;
+    use crate::shared_session::SharedSession;
+
+    #[derive(Debug)]
+    struct SharedItem {
+        key: i32,
+    }
+
+    impl DItem for SharedItem {
+        fn key(&self) -> i32 { self.key }
+        fn item_type(&self) -> u16 { 500 }
+        fn table_type(&self) -> u16 { 50 }
+        fn serialize(&self, _stream: &mut dyn crate::tx_stream::TxStream, _session: &Session) {}
+    }
+
+    fn new_shared(timeout: Duration) -> SharedSession {
+        item_factory_mut().lock().unwrap().register_type(
+            500,
+            50,
+            Arc::new(|key| Arc::new(SharedItem { key })),
+            Arc::new(|_item| {}),
+        );
+        let mut session = Session::new();
+        session.register_table(50, 500);
+        SharedSession::with_lock_timeout(session, timeout)
+    }
+
+    #[test]
+    fn test_threads_edit_disjoint_keys() {
+        let shared = new_shared(Duration::from_secs(5));
+
+        let workers: Vec<_> = (0..4)
+            .map(|n| {
+                let shared = shared.clone();
+                thread::spawn(move || {
+                    let mut tx = shared.begin();
+                    for key in n * 10..n * 10 + 10 {
+                        assert!(tx.insert(50, key).unwrap().is_some());
+                    }
+                    tx.commit().unwrap();
+                })
+            })
+            .collect();
+        for worker in workers {
+            worker.join().unwrap();
+        }
+
+        let session = shared.session();
+        assert!((0..40).all(|key| session.get_table(50).unwrap().get(key).is_some()));
+        assert_eq!(session.history().len(), 4); // 트랜잭션마다 커밋 하나
+    }
+
+    #[test]
+    fn test_lock_timeout_and_rollback() {
+        let shared = new_shared(Duration::from_millis(50));
+
+        let mut writer = shared.begin();
+        writer.insert(50, 1).unwrap();
+
+        // 커밋 전 배타 잠금이 걸린 키는 다른 트랜잭션이 읽을 수 없음
+        let reader = shared.begin();
+        assert_eq!(reader.get(50, 1).unwrap_err(), LockError::Timeout { table_type: 50, key: 1 });
+        assert!(reader.get(50, 3).unwrap().is_none()); // 다른 키는 자유
+
+        // 다른 트랜잭션의 커밋에 섞이지 않음
+        let mut other = shared.begin();
+        other.insert(50, 2).unwrap();
+        other.commit().unwrap();
+        writer.rollback();
+
+        assert!(reader.get(50, 1).unwrap().is_none());
+        assert!(reader.get(50, 2).unwrap().is_some());
+        shared.session().undo_all().unwrap();
+        assert!(shared.session().get_table(50).unwrap().get(2).is_none());
+    }
+
+    #[test]
+    fn test_subscribers_can_use_the_shared_session() {
+        let shared = new_shared(Duration::from_millis(200));
+        let seen: Arc<Mutex<Vec<(ChangeSource, usize)>>> = Arc::default();
+        let table_calls: Arc<Mutex<usize>> = Arc::default();
+        {
+            let (watcher, seen) = (shared.clone(), seen.clone());
+            shared.session().subscribe(Arc::new(move |batch| {
+                // 알림은 세션 잠금과 키 잠금을 놓은 뒤에 오므로 같은 세션을 다시 읽어도 교착되지 않음
+                let count = watcher.session().get_table(50).unwrap().items.count();
+                watcher.begin().get(50, batch.events[0].key()).unwrap();
+                seen.lock().unwrap().push((batch.source, count));
+            }));
+            let (watcher, table_calls) = (shared.clone(), table_calls.clone());
+            shared.session().get_table_mut(50).unwrap().subscribe(Arc::new(move |_batch| {
+                watcher.session().history();
+                *table_calls.lock().unwrap() += 1;
+            }));
+        }
+
+        let mut tx = shared.begin();
+        tx.insert(50, 1).unwrap();
+        tx.insert(50, 2).unwrap();
+        tx.commit().unwrap();
+        shared.session().undo_all().unwrap();
+
+        assert_eq!(*seen.lock().unwrap(), vec![(ChangeSource::Commit, 2), (ChangeSource::Undo, 0)]);
+        assert_eq!(*table_calls.lock().unwrap(), 2);
+    }
+
+    #[derive(Debug, DItem)]
+    #[ditem(item_type = 2100, table_type = 210)]
+    struct Folder {
+        #[key]
+        id: i32,
+    }
+
+    #[derive(Debug, DItem)]
+    #[ditem(item_type = 2101, table_type = 211)]
+    struct File {
+        #[key]
+        id: i32,
+        folder: i32,
+    }
+
+    #[test]
+    fn test_cascade_needs_referrer_locks() {
+        let factory = item_factory_mut();
+        assert!(Folder::register(factory));
+        assert!(File::register(factory));
+        let mut session = Session::new();
+        session.register_table(210, 2100);
+        session.register_table(211, 2101);
+        let folder_of = |item: &dyn DItem| item.downcast_ref::<File>().map(|f| f.folder);
+        assert!(session.add_reference(Reference::new("folder", (211, 2101), (210, 2100), folder_of).cascade()));
+        session.get_table_mut(210).unwrap().insert(1, factory);
+        session.get_table_mut(211).unwrap().insert_item(Arc::new(File { id: 7, folder: 1 }));
+        session.commit_all().unwrap();
+        let shared = SharedSession::with_lock_timeout(session, Duration::from_millis(50));
+
+        // 다른 트랜잭션이 배타 잠금한 파일은 연쇄 삭제하지 않음
+        let mut editor = shared.begin();
+        editor.modify(211, 7, Arc::new(File { id: 7, folder: 1 })).unwrap();
+        let mut remover = shared.begin();
+        remover.remove(210, 1).unwrap();
+        let err = remover.commit().unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::WouldBlock);
+        let reference_error = err.get_ref().unwrap().downcast_ref::<ReferenceError>().unwrap();
+        assert!(matches!(reference_error, ReferenceError::Locked { referrer_table: 211, referrer_key: 7, .. }));
+        assert!(shared.session().get_table(210).unwrap().get(1).is_some());
+        editor.commit().unwrap();
+
+        // 잠금이 풀리면 함께 삭제
+        let mut remover = shared.begin();
+        remover.remove(210, 1).unwrap();
+        remover.commit().unwrap();
+        let session = shared.session();
+        assert!(session.get_table(210).unwrap().get(1).is_none());
+        assert!(session.get_table(211).unwrap().get(7).is_none());
+    }
+
+    #[test]
+    fn test_deadlock_is_reported() {
+        let shared = new_shared(Duration::from_secs(5));
+
+        let mut first = shared.begin();
+        let mut second = shared.begin();
+        first.insert(50, 1).unwrap();
+        second.insert(50, 2).unwrap();
+
+        // second 는 key 1 을 기다림
+        let waiter = thread::spawn(move || {
+            let result = second.lock(50, 1, LockMode::Exclusive);
+            second.commit().unwrap();
+            result
+        });
+        thread::sleep(Duration::from_millis(100));
+
+        // first 가 key 2 를 요청하면 순환 대기
+        let err = first.lock(50, 2, LockMode::Shared).unwrap_err();
+        assert_eq!(err, LockError::Deadlock { table_type: 50, key: 2 });
+        assert_eq!(std::io::Error::from(err).kind(), std::io::ErrorKind::Deadlock);
+        first.rollback(); // 잠금이 풀리면 second 가 진행
+        assert!(waiter.join().unwrap().is_ok());
+
+        let session = shared.session();
+        assert!(session.get_table(50).unwrap().get(1).is_none());
+        assert!(session.get_table(50).unwrap().get(2).is_some());
+    }
+}
@@ -0,0 +1,115 @@
+use std::io;
+use std::sync::Mutex;
+
+use crate::item::Cursor;
+use crate::item_factory::ItemFactory;
+use crate::session::Session;
+use crate::tx_stream::{FileTxStream, MemTxStream, TxStream};
+
+// 스냅샷 파일 헤더
+const SNAPSHOT_MAGIC: u32 = 0x5353_584E; // "NXSS"
+const SNAPSHOT_VERSION: u32 = 2; // 2: 아이템마다 스키마 버전 기록 (1 은 모두 스키마 버전 1 로 읽음)
+
+/// 스냅샷 기록: 헤더 → 테이블 목록(table_type, item_type, 개수) → 아이템 (스키마 버전 + 페이로드)
+/// 임시 파일에 쓴 뒤 이름을 바꿔 기존 스냅샷을 원자적으로 교체
+pub fn write_snapshot(session: &Session, path: &str) -> io::Result<()> {
+    let tmp_path = format!("{}.tmp", path);
+    {
+        let mut stream = FileTxStream::new_write(&tmp_path)?;
+        stream.write_u32(SNAPSHOT_MAGIC);
+        stream.write_u32(SNAPSHOT_VERSION);
+
+        let mut table_types = session.table_types();
+        table_types.sort_unstable();
+
+        stream.write_u32(table_types.len() as u32);
+        for table_type in &table_types {
+            let table = session.get_table(*table_type).unwrap();
+            stream.write_u16(table.table_type);
+            stream.write_u16(table.item_type);
+            stream.write_u32(table.items.all_items().count() as u32);
+        }
+
+        for table_type in &table_types {
+            let table = session.get_table(*table_type).unwrap();
+            let mut cursors: Vec<&Cursor> = table.items.all_items().collect();
+            cursors.sort_by_key(|c| c.key());
+            for cursor in cursors {
+                stream.write_u32(cursor.key() as u32);
+                stream.write_u8(cursor.visible as u8);
+                stream.write_u8(cursor.param_data);
+                stream.write_u32(cursor.param as u32);
+
+                let mut payload = MemTxStream::new();
+                cursor.data.serialize(&mut payload, session);
+                stream.write_u16(cursor.data.schema_version());
+                stream.write_u32(payload.as_bytes().len() as u32);
+                stream.write_bytes(payload.as_bytes());
+            }
+        }
+        stream.sync()?;
+    }
+    std::fs::rename(&tmp_path, path)
+}
+
+/// 스냅샷 읽기: 이전 스키마 버전의 페이로드는 등록된 변환 함수로 올린 뒤 복원
+pub fn read_snapshot(path: &str, factory: &Mutex<ItemFactory>) -> io::Result<Session> {
+    let mut stream = FileTxStream::new_read(path)?;
+    if stream.read_u32() != Some(SNAPSHOT_MAGIC) {
+        return Err(invalid("not a session snapshot"));
+    }
+    let file_version = stream.read_u32().ok_or_else(truncated)?;
+    if file_version == 0 || file_version > SNAPSHOT_VERSION {
+        return Err(invalid("unsupported session snapshot version"));
+    }
+
+    let table_count = stream.read_u32().ok_or_else(truncated)?;
+    let mut catalog = Vec::new();
+    for _ in 0..table_count {
+        let table_type = stream.read_u16().ok_or_else(truncated)?;
+        let item_type = stream.read_u16().ok_or_else(truncated)?;
+        let item_count = stream.read_u32().ok_or_else(truncated)?;
+        catalog.push((table_type, item_type, item_count));
+    }
+
+    let mut session = Session::new();
+    for (table_type, item_type, item_count) in catalog {
+        session.register_table(table_type, item_type);
+        let table = session.get_table_mut(table_type).unwrap();
+
+        for _ in 0..item_count {
+            let key = stream.read_u32().ok_or_else(truncated)? as i32;
+            let visible = stream.read_u8().ok_or_else(truncated)? != 0;
+            let param_data = stream.read_u8().ok_or_else(truncated)?;
+            let param = stream.read_u32().ok_or_else(truncated)? as usize;
+            let schema_version = if file_version >= 2 {
+                stream.read_u16().ok_or_else(truncated)?
+            } else {
+                1
+            };
+            let payload_len = stream.read_u32().ok_or_else(truncated)? as usize;
+            let payload = stream.read_bytes(payload_len).ok_or_else(truncated)?;
+
+            let item = factory
+                .lock()
+                .map_err(|_| invalid("item factory lock poisoned"))?
+                .deserialize_item(item_type, key, schema_version, payload)?;
+
+            let mut cursor = Cursor::new(item);
+            cursor.visible = visible;
+            cursor.param_data = param_data;
+            cursor.param = param;
+            table.items.insert(cursor);
+        }
+        table.rebuild_indexes();
+    }
+    Ok(session)
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+fn truncated() -> io::Error {
+    io::Error::new(io::ErrorKind::UnexpectedEof, "session snapshot is truncated")
+}
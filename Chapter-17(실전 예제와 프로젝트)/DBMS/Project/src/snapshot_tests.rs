@@ -0,0 +1,92 @@
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use crate::dbutil::get_db_temp_path;
+    use crate::item::DItem;
+    use crate::session::Session;
+    use crate::item_factory::item_factory_mut;
+    use crate::tx_stream::TxStream;
+
+    #[derive(Debug)]
+    struct SnapItem {
+        key: i32,
+    }
+
+    impl DItem for SnapItem {
+        fn key(&self) -> i32 { self.key }
+        fn item_type(&self) -> u16 { 300 }
+        fn table_type(&self) -> u16 { 30 }
+        fn serialize(&self, stream: &mut dyn TxStream, _session: &Session) {
+            stream.write_u32(self.key as u32);
+        }
+    }
+
+    fn register() {
+        let mut factory = item_factory_mut().lock().unwrap();
+        factory.register_type(
+            300,
+            30,
+            Arc::new(|key| Arc::new(SnapItem { key })),
+            Arc::new(|_item| {}),
+        );
+        factory.register_deserializer(
+            300,
+            Arc::new(|_key, stream| Some(Arc::new(SnapItem { key: stream.read_u32()? as i32 }))),
+        );
+    }
+
+    fn temp_file(name: &str) -> String {
+        let dir = get_db_temp_path();
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        let _ = std::fs::remove_file(&path);
+        path.to_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn test_snapshot_save_load() {
+        register();
+        let path = temp_file("snapshot_save_load.snap");
+
+        let mut session = Session::new();
+        session.register_table(30, 300);
+        let table = session.get_table_mut(30).unwrap();
+        table.insert(1, item_factory_mut()).unwrap();
+        table.insert(2, item_factory_mut()).unwrap();
+        if let Some(list) = table.items.find_mut(2) {
+            list[0].set_param(7);
+        }
+        session.save_snapshot(&path).unwrap();
+
+        let loaded = Session::load_snapshot(&path).unwrap();
+        let table = loaded.get_table(30).unwrap();
+        assert_eq!(table.item_type, 300);
+        assert_eq!(table.items.count(), 2);
+        assert!(table.get(1).is_some());
+        assert_eq!(table.get(2).unwrap().param, 7);
+    }
+
+    #[test]
+    fn test_checkpoint_with_log_replay() {
+        register();
+        let snap_path = temp_file("checkpoint.snap");
+        let log_path = temp_file("checkpoint.log");
+
+        {
+            let mut session = Session::open_with_snapshot(&snap_path, &log_path).unwrap();
+            session.register_table(30, 300);
+            session.get_table_mut(30).unwrap().insert(1, item_factory_mut());
+            session.commit_all().unwrap();
+            session.checkpoint(&snap_path).unwrap();
+
+            session.get_table_mut(30).unwrap().insert(2, item_factory_mut());
+            session.commit_all().unwrap();
+        }
+
+        let session = Session::open_with_snapshot(&snap_path, &log_path).unwrap();
+        let table = session.get_table(30).unwrap();
+        assert!(table.get(1).is_some()); // 스냅샷
+        assert!(table.get(2).is_some()); // 로그
+        assert_eq!(table.items.count(), 2);
+    }
+}
@@ -0,0 +1,646 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::item::Cursor;
+use crate::item_factory::item_factory;
+use crate::session::Session;
+use crate::transaction::Transaction;
+
+/// 원본 위치 (1부터 시작)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+/// 구문 또는 실행 오류
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SqlError {
+    pub message: String,
+    pub position: Position,
+}
+
+impl SqlError {
+    fn new(message: impl Into<String>, position: Position) -> Self {
+        SqlError {
+            message: message.into(),
+            position,
+        }
+    }
+}
+
+impl fmt::Display for SqlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.position.line, self.position.column, self.message)
+    }
+}
+
+impl std::error::Error for SqlError {}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Word(String), // 키워드 또는 이름 (대문자로 저장)
+    Number(i64),
+    Text(String),
+    Symbol(&'static str),
+    End,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Word(w) => write!(f, "'{}'", w),
+            Token::Number(n) => write!(f, "{}", n),
+            Token::Text(t) => write!(f, "'{}'", t),
+            Token::Symbol(s) => write!(f, "'{}'", s),
+            Token::End => write!(f, "end of input"),
+        }
+    }
+}
+
+const SYMBOLS: [&str; 12] = ["<=", ">=", "<>", "!=", "*", ",", ";", "(", ")", "=", "<", ">"];
+
+fn tokenize(source: &str) -> Result<Vec<(Token, Position)>, SqlError> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let (mut i, mut line, mut column) = (0, 1, 1);
+
+    while i < chars.len() {
+        let c = chars[i];
+        let position = Position { line, column };
+        let start = i;
+
+        if c == '\n' {
+            i += 1;
+            line += 1;
+            column = 1;
+            continue;
+        }
+        if c.is_whitespace() {
+            i += 1;
+            column += 1;
+            continue;
+        }
+        if c == '-' && chars.get(i + 1) == Some(&'-') {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1; // 주석
+            }
+            continue;
+        }
+
+        if c.is_ascii_digit() || (c == '-' && chars.get(i + 1).is_some_and(|d| d.is_ascii_digit())) {
+            i += 1;
+            while i < chars.len() && chars[i].is_ascii_digit() {
+                i += 1;
+            }
+            let text: String = chars[start..i].iter().collect();
+            let value = text
+                .parse::<i64>()
+                .map_err(|_| SqlError::new(format!("number {} is out of range", text), position))?;
+            tokens.push((Token::Number(value), position));
+        } else if c.is_alphabetic() || c == '_' {
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            let word: String = chars[start..i].iter().collect();
+            tokens.push((Token::Word(word.to_uppercase()), position));
+        } else if c == '\'' {
+            i += 1;
+            let mut text = String::new();
+            loop {
+                match chars.get(i) {
+                    None | Some('\n') => return Err(SqlError::new("unterminated string", position)),
+                    Some('\'') if chars.get(i + 1) == Some(&'\'') => {
+                        text.push('\'');
+                        i += 2;
+                    }
+                    Some('\'') => {
+                        i += 1;
+                        break;
+                    }
+                    Some(ch) => {
+                        text.push(*ch);
+                        i += 1;
+                    }
+                }
+            }
+            tokens.push((Token::Text(text), position));
+        } else {
+            let rest: String = chars[i..chars.len().min(i + 2)].iter().collect();
+            let Some(symbol) = SYMBOLS.iter().find(|s| rest.starts_with(**s)) else {
+                return Err(SqlError::new(format!("unexpected character '{}'", c), position));
+            };
+            i += symbol.chars().count();
+            tokens.push((Token::Symbol(symbol), position));
+        }
+        column += i - start;
+    }
+    tokens.push((Token::End, Position { line, column }));
+    Ok(tokens)
+}
+
+/// 키 조건 (WHERE 절, AND 로 연결)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum KeyCondition {
+    Between(i32, i32),
+    Compare(&'static str, i32), // =, <>, <, <=, >, >=
+    In(Vec<i32>),
+}
+
+impl KeyCondition {
+    fn matches(&self, key: i32) -> bool {
+        match self {
+            KeyCondition::Between(lo, hi) => (*lo..=*hi).contains(&key),
+            KeyCondition::Compare(op, n) => match *op {
+                "=" => key == *n,
+                "<>" => key != *n,
+                "<" => key < *n,
+                "<=" => key <= *n,
+                ">" => key > *n,
+                _ => key >= *n,
+            },
+            KeyCondition::In(keys) => keys.contains(&key),
+        }
+    }
+
+    /// 조건을 만족하는 키의 범위 (포함 경계)
+    fn bounds(&self) -> (i64, i64) {
+        let (min, max) = (i32::MIN as i64, i32::MAX as i64);
+        match self {
+            KeyCondition::Between(lo, hi) => (*lo as i64, *hi as i64),
+            KeyCondition::Compare(op, n) => {
+                let n = *n as i64;
+                match *op {
+                    "=" => (n, n),
+                    "<" => (min, n - 1),
+                    "<=" => (min, n),
+                    ">" => (n + 1, max),
+                    ">=" => (n, max),
+                    _ => (min, max),
+                }
+            }
+            KeyCondition::In(keys) => match (keys.iter().min(), keys.iter().max()) {
+                (Some(lo), Some(hi)) => (*lo as i64, *hi as i64),
+                _ => (1, 0), // 빈 목록
+            },
+        }
+    }
+}
+
+/// SELECT 결과 형태
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Projection {
+    All,   // *
+    Key,   // key
+    Count, // COUNT(*)
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StatementKind {
+    Select {
+        table_type: u16,
+        projection: Projection,
+        conditions: Vec<KeyCondition>,
+        descending: bool,
+        limit: Option<usize>,
+        offset: usize,
+    },
+    Delete {
+        table_type: u16,
+        conditions: Vec<KeyCondition>,
+    },
+    Insert {
+        table_type: u16,
+        keys: Vec<(i32, Position)>,
+    },
+    Begin,
+    Commit(String), // 설명
+    Rollback,
+    Undo,
+    Redo,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Statement {
+    pub kind: StatementKind,
+    pub position: Position,
+    table_position: Position, // FROM/INTO 뒤 테이블 번호 위치 (실행 오류 표시용)
+}
+
+struct Parser {
+    tokens: Vec<(Token, Position)>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.pos].0
+    }
+
+    fn position(&self) -> Position {
+        self.tokens[self.pos].1
+    }
+
+    fn advance(&mut self) -> (Token, Position) {
+        let token = self.tokens[self.pos].clone();
+        if self.pos + 1 < self.tokens.len() {
+            self.pos += 1;
+        }
+        token
+    }
+
+    fn unexpected(&self, expected: &str) -> SqlError {
+        SqlError::new(format!("expected {}, found {}", expected, self.peek()), self.position())
+    }
+
+    fn is_word(&self, word: &str) -> bool {
+        matches!(self.peek(), Token::Word(w) if w == word)
+    }
+
+    fn eat_word(&mut self, word: &str) -> bool {
+        if self.is_word(word) {
+            self.advance();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect_word(&mut self, word: &str) -> Result<(), SqlError> {
+        if self.eat_word(word) {
+            Ok(())
+        } else {
+            Err(self.unexpected(word))
+        }
+    }
+
+    fn eat_symbol(&mut self, symbol: &str) -> bool {
+        if matches!(self.peek(), Token::Symbol(s) if *s == symbol) {
+            self.advance();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect_symbol(&mut self, symbol: &str) -> Result<(), SqlError> {
+        if self.eat_symbol(symbol) {
+            Ok(())
+        } else {
+            Err(self.unexpected(&format!("'{}'", symbol)))
+        }
+    }
+
+    fn number<T: TryFrom<i64>>(&mut self, what: &str) -> Result<(T, Position), SqlError> {
+        let position = self.position();
+        match self.peek().clone() {
+            Token::Number(n) => {
+                self.advance();
+                T::try_from(n)
+                    .map(|v| (v, position))
+                    .map_err(|_| SqlError::new(format!("{} {} is out of range", what, n), position))
+            }
+            _ => Err(self.unexpected(what)),
+        }
+    }
+
+    fn statements(&mut self) -> Result<Vec<Statement>, SqlError> {
+        let mut statements = Vec::new();
+        loop {
+            while self.eat_symbol(";") {}
+            if *self.peek() == Token::End {
+                return Ok(statements);
+            }
+            statements.push(self.statement()?);
+            if *self.peek() != Token::End {
+                self.expect_symbol(";")?;
+            }
+        }
+    }
+
+    fn statement(&mut self) -> Result<Statement, SqlError> {
+        let position = self.position();
+        let mut table_position = position;
+        let kind = match self.advance().0 {
+            Token::Word(w) if w == "SELECT" => {
+                let projection = if self.eat_symbol("*") {
+                    Projection::All
+                } else if self.eat_word("KEY") {
+                    Projection::Key
+                } else if self.eat_word("COUNT") {
+                    self.expect_symbol("(")?;
+                    self.expect_symbol("*")?;
+                    self.expect_symbol(")")?;
+                    Projection::Count
+                } else {
+                    return Err(self.unexpected("'*', 'KEY' or 'COUNT(*)'"));
+                };
+                self.expect_word("FROM")?;
+                let (table_type, at) = self.number("table type")?;
+                table_position = at;
+                let conditions = self.conditions()?;
+                let mut descending = false;
+                if self.eat_word("ORDER") {
+                    self.expect_word("BY")?;
+                    self.expect_word("KEY")?;
+                    descending = self.eat_word("DESC");
+                    if !descending {
+                        self.eat_word("ASC");
+                    }
+                }
+                let mut limit = None;
+                let mut offset = 0;
+                if self.eat_word("LIMIT") {
+                    limit = Some(self.number("limit")?.0);
+                    if self.eat_word("OFFSET") {
+                        offset = self.number("offset")?.0;
+                    }
+                }
+                StatementKind::Select {
+                    table_type,
+                    projection,
+                    conditions,
+                    descending,
+                    limit,
+                    offset,
+                }
+            }
+            Token::Word(w) if w == "DELETE" => {
+                self.expect_word("FROM")?;
+                let (table_type, at) = self.number("table type")?;
+                table_position = at;
+                StatementKind::Delete {
+                    table_type,
+                    conditions: self.conditions()?,
+                }
+            }
+            Token::Word(w) if w == "INSERT" => {
+                self.expect_word("INTO")?;
+                let (table_type, at) = self.number("table type")?;
+                table_position = at;
+                self.expect_word("VALUES")?;
+                let mut keys = Vec::new();
+                loop {
+                    self.expect_symbol("(")?;
+                    keys.push(self.number("key")?);
+                    self.expect_symbol(")")?;
+                    if !self.eat_symbol(",") {
+                        break;
+                    }
+                }
+                StatementKind::Insert { table_type, keys }
+            }
+            Token::Word(w) if w == "BEGIN" => StatementKind::Begin,
+            Token::Word(w) if w == "COMMIT" => match self.peek().clone() {
+                Token::Text(label) => {
+                    self.advance();
+                    StatementKind::Commit(label)
+                }
+                _ => StatementKind::Commit(String::new()),
+            },
+            Token::Word(w) if w == "ROLLBACK" => StatementKind::Rollback,
+            Token::Word(w) if w == "UNDO" => StatementKind::Undo,
+            Token::Word(w) if w == "REDO" => StatementKind::Redo,
+            token => {
+                return Err(SqlError::new(format!("expected a statement, found {}", token), position));
+            }
+        };
+        Ok(Statement {
+            kind,
+            position,
+            table_position,
+        })
+    }
+
+    /// WHERE key ... [AND key ...]
+    fn conditions(&mut self) -> Result<Vec<KeyCondition>, SqlError> {
+        let mut conditions = Vec::new();
+        if !self.eat_word("WHERE") {
+            return Ok(conditions);
+        }
+        loop {
+            self.expect_word("KEY")?;
+            let condition = if self.eat_word("BETWEEN") {
+                let (lo, _) = self.number("key")?;
+                self.expect_word("AND")?;
+                let (hi, _) = self.number("key")?;
+                KeyCondition::Between(lo, hi)
+            } else if self.eat_word("IN") {
+                self.expect_symbol("(")?;
+                let mut keys = vec![self.number("key")?.0];
+                while self.eat_symbol(",") {
+                    keys.push(self.number("key")?.0);
+                }
+                self.expect_symbol(")")?;
+                KeyCondition::In(keys)
+            } else {
+                let op = match self.peek() {
+                    Token::Symbol(s) if ["=", "<>", "!=", "<", "<=", ">", ">="].contains(s) => *s,
+                    _ => return Err(self.unexpected("'BETWEEN', 'IN' or a comparison")),
+                };
+                self.advance();
+                let op = if op == "!=" { "<>" } else { op };
+                KeyCondition::Compare(op, self.number("key")?.0)
+            };
+            conditions.push(condition);
+            if !self.eat_word("AND") {
+                return Ok(conditions);
+            }
+        }
+    }
+}
+
+/// 문장 목록으로 구문 분석
+pub fn parse(source: &str) -> Result<Vec<Statement>, SqlError> {
+    let mut parser = Parser {
+        tokens: tokenize(source)?,
+        pos: 0,
+    };
+    parser.statements()
+}
+
+/// 문장 하나의 실행 결과
+#[derive(Clone, Debug)]
+pub enum SqlResult {
+    Rows(Vec<Cursor>),
+    Keys(Vec<i32>),
+    Count(usize),
+    Affected(usize), // 삽입/삭제된 아이템 수
+    Done,
+}
+
+/// 세션에 문장을 실행: BEGIN 밖의 변경 문장은 하나씩 바로 커밋,
+/// BEGIN ~ COMMIT/ROLLBACK 사이의 변경은 함께 커밋 (drop 시 열린 블록은 롤백)
+pub struct SqlExecutor<'a> {
+    session: &'a mut Session,
+    in_block: bool,
+}
+
+impl<'a> SqlExecutor<'a> {
+    pub fn new(session: &'a mut Session) -> Self {
+        SqlExecutor {
+            session,
+            in_block: false,
+        }
+    }
+
+    pub fn in_transaction(&self) -> bool {
+        self.in_block
+    }
+
+    /// 원본을 분석해 순서대로 실행 (첫 오류에서 멈춤)
+    pub fn execute(&mut self, source: &str) -> Result<Vec<SqlResult>, SqlError> {
+        let statements = parse(source)?;
+        statements.iter().map(|statement| self.run(statement)).collect()
+    }
+
+    pub fn run(&mut self, statement: &Statement) -> Result<SqlResult, SqlError> {
+        let at = statement.position;
+        match &statement.kind {
+            StatementKind::Select {
+                table_type,
+                projection,
+                conditions,
+                descending,
+                limit,
+                offset,
+            } => {
+                self.check_table(*table_type, statement)?;
+                let keys = self.matching_keys(*table_type, conditions, *descending);
+                let keys = keys.into_iter().skip(*offset).take(limit.unwrap_or(usize::MAX));
+                let table = self.session.get_table(*table_type).unwrap();
+                Ok(match projection {
+                    Projection::All => SqlResult::Rows(keys.filter_map(|k| table.get(k).cloned()).collect()),
+                    Projection::Key => SqlResult::Keys(keys.collect()),
+                    Projection::Count => SqlResult::Count(keys.count()),
+                })
+            }
+            StatementKind::Delete { table_type, conditions } => {
+                self.check_table(*table_type, statement)?;
+                let keys = self.matching_keys(*table_type, conditions, false);
+                let table_type = *table_type;
+                self.write(at, move |session| {
+                    let table = session.get_table_mut(table_type).unwrap();
+                    Ok(keys.into_iter().filter(|key| table.remove(*key)).count())
+                })
+            }
+            StatementKind::Insert { table_type, keys } => {
+                self.check_table(*table_type, statement)?;
+                let table_type = *table_type;
+                self.write(at, move |session| {
+                    let table = session.get_table_mut(table_type).unwrap();
+                    for (key, position) in keys {
+                        if table.get(*key).is_some() {
+                            return Err(SqlError::new(format!("key {} already exists", key), *position));
+                        }
+                        if table.insert(*key, item_factory()).is_none() {
+                            return Err(SqlError::new(format!("cannot create item for key {}", key), *position));
+                        }
+                    }
+                    Ok(keys.len())
+                })
+            }
+            StatementKind::Begin => {
+                if self.in_block {
+                    return Err(SqlError::new("transaction already started", at));
+                }
+                if self.has_pending() {
+                    return Err(SqlError::new("session has uncommitted changes", at));
+                }
+                self.in_block = true;
+                Ok(SqlResult::Done)
+            }
+            StatementKind::Commit(label) => {
+                if !self.in_block {
+                    return Err(SqlError::new("no transaction to commit", at));
+                }
+                // 커밋이 실패하면 블록은 열린 채로 남음 (ROLLBACK 또는 drop 에서 취소)
+                self.session.commit_labelled(label).map_err(|e| SqlError::new(e.to_string(), at))?;
+                self.in_block = false;
+                Ok(SqlResult::Done)
+            }
+            StatementKind::Rollback => {
+                if !self.in_block {
+                    return Err(SqlError::new("no transaction to roll back", at));
+                }
+                self.in_block = false;
+                self.session.rollback_pending_to(&HashMap::new());
+                Ok(SqlResult::Done)
+            }
+            StatementKind::Undo | StatementKind::Redo => {
+                if self.in_block {
+                    return Err(SqlError::new("cannot undo or redo inside a transaction", at));
+                }
+                let result = if statement.kind == StatementKind::Undo {
+                    self.session.undo_all()
+                } else {
+                    self.session.redo_all()
+                };
+                result.map_err(|e| SqlError::new(e.to_string(), at))?;
+                Ok(SqlResult::Done)
+            }
+        }
+    }
+
+    fn check_table(&self, table_type: u16, statement: &Statement) -> Result<(), SqlError> {
+        if self.session.get_table(table_type).is_none() {
+            return Err(SqlError::new(format!("unknown table {}", table_type), statement.table_position));
+        }
+        Ok(())
+    }
+
+    fn has_pending(&self) -> bool {
+        self.session.tables.values().any(|t| t.tx.current_count() > 0)
+    }
+
+    /// 조건에 맞는 키 (키 순): 조건들의 공통 범위만 조회
+    fn matching_keys(&self, table_type: u16, conditions: &[KeyCondition], descending: bool) -> Vec<i32> {
+        let (lo, hi) = conditions.iter().map(|c| c.bounds()).fold(
+            (i32::MIN as i64, i32::MAX as i64),
+            |(lo, hi), (l, h)| (lo.max(l), hi.min(h)),
+        );
+        if lo > hi {
+            return Vec::new();
+        }
+        self.session
+            .query(table_type)
+            .key_range(lo as i32..=hi as i32)
+            .filter(|c| conditions.iter().all(|cond| cond.matches(c.key())))
+            .order_by_key(descending)
+            .keys()
+    }
+
+    /// 변경 문장: 블록 안이면 실패 시 이 문장의 변경만 되돌리고, 밖이면 트랜잭션으로 바로 커밋
+    /// 밖에서는 남아 있는 변경을 함께 커밋하지 않도록 미커밋 변경이 있으면 거부
+    fn write(
+        &mut self,
+        at: Position,
+        change: impl FnOnce(&mut Session) -> Result<usize, SqlError>,
+    ) -> Result<SqlResult, SqlError> {
+        if self.in_block {
+            let saved = self.session.pending_state();
+            return match change(self.session) {
+                Ok(count) => Ok(SqlResult::Affected(count)),
+                Err(e) => {
+                    self.session.rollback_pending_to(&saved);
+                    Err(e)
+                }
+            };
+        }
+        if self.has_pending() {
+            return Err(SqlError::new("session has uncommitted changes", at));
+        }
+        let mut tx = Transaction::new(self.session); // 지금 상태를 저장점으로 기록
+        let count = change(tx.session())?; // 실패하면 drop 에서 저장점으로 롤백
+        tx.commit().map_err(|e| SqlError::new(e.to_string(), at))?;
+        Ok(SqlResult::Affected(count))
+    }
+}
+
+impl Drop for SqlExecutor<'_> {
+    fn drop(&mut self) {
+        if self.in_block {
+            self.session.rollback_pending_to(&HashMap::new()); // 끝나지 않은 블록은 롤백
+        }
+    }
+}
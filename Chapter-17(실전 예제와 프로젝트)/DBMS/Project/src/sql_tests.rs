@@ -0,0 +1,145 @@
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use crate::dbutil::get_db_temp_path;
+    use crate::item::DItem;
+    use crate::item_factory::item_factory_mut;
+    use crate::session::Session;
+    use crate::sql::{Position, SqlExecutor, SqlResult};
+
+    #[derive(Debug)]
+    struct Doc {
+        key: i32,
+    }
+
+    impl DItem for Doc {
+        fn key(&self) -> i32 { self.key }
+        fn item_type(&self) -> u16 { 1100 }
+        fn table_type(&self) -> u16 { 110 }
+        fn serialize(&self, _stream: &mut dyn crate::tx_stream::TxStream, _session: &Session) {}
+    }
+
+    fn new_session() -> Session {
+        item_factory_mut().lock().unwrap().register_type(1100, 110, Arc::new(|key| Arc::new(Doc { key })), Arc::new(|_item| {}));
+        let mut session = Session::new();
+        session.register_table(110, 1100);
+        session
+    }
+
+    fn keys(result: &SqlResult) -> Vec<i32> {
+        match result {
+            SqlResult::Keys(keys) => keys.clone(),
+            SqlResult::Rows(rows) => rows.iter().map(|c| c.key()).collect(),
+            other => panic!("expected rows, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_statements_map_onto_session() {
+        let mut session = new_session();
+        let results = session
+            .execute(
+                "insert into 110 values (5), (1), (3), (9);
+                 SELECT key FROM 110 WHERE key BETWEEN 2 AND 9 ORDER BY key DESC LIMIT 2;
+                 SELECT * FROM 110 WHERE key IN (1, 9, 4) AND key <> 9;
+                 DELETE FROM 110 WHERE key >= 5; -- 5, 9
+                 SELECT COUNT(*) FROM 110",
+            )
+            .unwrap();
+        assert!(matches!(results[0], SqlResult::Affected(4)));
+        assert_eq!(keys(&results[1]), vec![9, 5]);
+        assert_eq!(keys(&results[2]), vec![1]);
+        assert!(matches!(results[3], SqlResult::Affected(2)));
+        assert!(matches!(results[4], SqlResult::Count(2)));
+        assert_eq!(session.history().len(), 2); // 문장마다 커밋
+
+        session.execute("UNDO").unwrap();
+        assert!(session.get_table(110).unwrap().get(9).is_some());
+        session.execute("REDO").unwrap();
+        assert!(session.get_table(110).unwrap().get(9).is_none());
+
+        // 블록 안의 변경은 COMMIT 에서 한 번에, ROLLBACK 은 블록 전체 취소
+        session.execute("BEGIN; INSERT INTO 110 VALUES (20); DELETE FROM 110; ROLLBACK").unwrap();
+        assert_eq!(session.get_table(110).unwrap().items.count(), 2);
+        session.execute("BEGIN; INSERT INTO 110 VALUES (20); INSERT INTO 110 VALUES (21); COMMIT 'bulk'").unwrap();
+        assert_eq!(session.history().last().unwrap().label, "bulk");
+
+        // 끝나지 않은 블록은 실행기가 사라질 때 롤백
+        {
+            let mut executor = SqlExecutor::new(&mut session);
+            executor.execute("BEGIN; DELETE FROM 110").unwrap();
+            assert!(executor.in_transaction());
+        }
+        assert_eq!(session.get_table(110).unwrap().items.count(), 4);
+    }
+
+    #[test]
+    fn test_errors_report_line_and_column() {
+        let mut session = new_session();
+
+        let err = session.execute("SELECT * FROM 110\n  WHERE key BETWEEN 1 9").unwrap_err();
+        assert_eq!(err.position, Position { line: 2, column: 23 });
+        assert_eq!(err.to_string(), "2:23: expected AND, found 9");
+
+        let err = session.execute("SELECT * FROM 404").unwrap_err();
+        assert_eq!(err.position, Position { line: 1, column: 15 });
+        assert_eq!(err.message, "unknown table 404");
+
+        // 실패한 문장의 변경은 남지 않음
+        let err = session.execute("INSERT INTO 110 VALUES (1);\nINSERT INTO 110 VALUES (2), (1)").unwrap_err();
+        assert_eq!(err.position, Position { line: 2, column: 30 });
+        assert!(session.get_table(110).unwrap().get(2).is_none());
+
+        let err = session.execute("COMMIT").unwrap_err();
+        assert_eq!(err.message, "no transaction to commit");
+        assert!(session.execute("SELECT # FROM 110").is_err());
+    }
+
+    #[test]
+    fn test_failed_commit_keeps_block_open() {
+        new_session();
+        let dir = get_db_temp_path();
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("sql_failed_commit.log");
+        let _ = std::fs::remove_file(&path);
+
+        // Doc 은 역직렬화 함수가 없어 로그에 기록할 수 없으므로 커밋 실패
+        let mut session = Session::open(path.to_str().unwrap()).unwrap();
+        session.register_table(110, 1100);
+        {
+            let mut executor = SqlExecutor::new(&mut session);
+            executor.execute("BEGIN; INSERT INTO 110 VALUES (1), (2)").unwrap();
+            assert!(executor.execute("COMMIT").is_err());
+            assert!(executor.in_transaction());
+
+            // 다음 문장은 블록 안에서 실행되고 ROLLBACK 이 블록 전체를 취소
+            executor.execute("INSERT INTO 110 VALUES (3)").unwrap();
+            executor.execute("ROLLBACK").unwrap();
+            assert!(!executor.in_transaction());
+            assert_eq!(executor.execute("SELECT COUNT(*) FROM 110").unwrap().len(), 1);
+        }
+        assert_eq!(session.get_table(110).unwrap().items.count(), 0);
+
+        // 실패한 블록을 끝내지 않고 실행기가 사라져도 롤백
+        {
+            let mut executor = SqlExecutor::new(&mut session);
+            executor.execute("BEGIN; INSERT INTO 110 VALUES (1)").unwrap();
+            assert!(executor.execute("COMMIT").is_err());
+        }
+        assert_eq!(session.get_table(110).unwrap().items.count(), 0);
+        assert!(session.history().is_empty());
+    }
+
+    #[test]
+    fn test_autocommit_leaves_other_pending_changes_alone() {
+        let mut session = new_session();
+        session.get_table_mut(110).unwrap().insert(7, item_factory_mut());
+
+        // 블록 밖 문장이 미커밋 변경을 함께 커밋하지 않음
+        let err = session.execute("INSERT INTO 110 VALUES (1)").unwrap_err();
+        assert_eq!(err.message, "session has uncommitted changes");
+        assert!(session.get_table(110).unwrap().get(1).is_none());
+        assert!(session.get_table(110).unwrap().get(7).is_some());
+        assert!(session.history().is_empty());
+    }
+}
@@ -0,0 +1,82 @@
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::ops::Bound;
+    use crate::hashset::{HashSetTable, StorageMode};
+    use crate::item::{Cursor, DItem};
+    use crate::item_factory::item_factory_mut;
+    use crate::session::Session;
+
+    #[derive(Debug)]
+    struct Row {
+        key: i32,
+    }
+
+    impl DItem for Row {
+        fn key(&self) -> i32 { self.key }
+        fn item_type(&self) -> u16 { 800 }
+        fn table_type(&self) -> u16 { 80 }
+        fn serialize(&self, _stream: &mut dyn crate::tx_stream::TxStream, _session: &Session) {}
+    }
+
+    fn keys<'a>(cursors: impl Iterator<Item = &'a Cursor>) -> Vec<i32> {
+        cursors.map(|c| c.key()).collect()
+    }
+
+    /// 뒤집힌 범위는 저장 방식과 상관없이 빈 결과 (panic 하지 않음)
+    fn assert_inverted_ranges_are_empty(items: &HashSetTable) {
+        let (low, high) = (10, 50);
+        assert!(items.range(high..low).next().is_none());
+        assert!(items.range_rev(high..=low).next().is_none());
+        assert!(items.range((Bound::Excluded(20), Bound::Excluded(20))).next().is_none());
+        assert!(items.range((Bound::Excluded(20), Bound::Included(20))).next().is_none());
+        assert_eq!(keys(items.range(20..=20)), vec![20]);
+    }
+
+    #[test]
+    fn test_ordered_range_reverse_and_paging() {
+        let factory = item_factory_mut();
+        factory.lock().unwrap().register_type(800, 80, Arc::new(|key| Arc::new(Row { key })), Arc::new(|_item| {}));
+
+        let mut session = Session::new();
+        session.register_table_with(80, 800, StorageMode::Ordered);
+        let table = session.get_table_mut(80).unwrap();
+        for key in [50, 10, 40, 20, 30] {
+            table.insert(key, factory);
+        }
+        table.remove(40);
+        session.commit_all().unwrap();
+
+        let items = &session.get_table(80).unwrap().items;
+        assert_eq!(keys(items.all_items()), vec![10, 20, 30, 50]);
+        let entries: Vec<(i32, usize)> = items.entries().map(|(key, list)| (*key, list.len())).collect();
+        assert_eq!(entries, vec![(10, 1), (20, 1), (30, 1), (50, 1)]);
+        assert!(items.contains_key(20) && !items.contains_key(40));
+        assert_eq!(keys(items.range(15..=50)), vec![20, 30, 50]);
+        assert_eq!(keys(items.range_rev(..30)), vec![20, 10]);
+        assert_eq!(items.first().unwrap().key(), 10);
+        assert_eq!(items.last().unwrap().key(), 50);
+        assert_inverted_ranges_are_empty(items);
+
+        // 키셋 페이지: next 를 다음 요청에 넘김
+        let first = items.page(None, 3);
+        assert_eq!(keys(first.items.iter()), vec![10, 20, 30]);
+        let second = items.page(first.next, 3);
+        assert_eq!(keys(second.items.iter()), vec![50]);
+        assert!(second.next.is_none());
+        let back = items.page_rev(Some(50), 2);
+        assert_eq!(keys(back.items.iter()), vec![30, 20]);
+        assert_eq!(back.next, Some(20));
+
+        // Hash 로 바꿔도 키 순 조회 결과는 같음, undo 후에도 순서 유지
+        let table = session.get_table_mut(80).unwrap();
+        table.set_storage_mode(StorageMode::Hash);
+        assert_eq!(keys(table.items.range(15..=50)), vec![20, 30, 50]);
+        assert_inverted_ranges_are_empty(&table.items);
+        table.set_storage_mode(StorageMode::Ordered);
+        session.undo_all().unwrap();
+        assert!(session.get_table(80).unwrap().items.first().is_none());
+        session.redo_all().unwrap();
+        assert_eq!(keys(session.get_table(80).unwrap().items.all_items()), vec![10, 20, 30, 50]);
+    }
+}
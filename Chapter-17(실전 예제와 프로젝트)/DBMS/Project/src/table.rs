@@ -1,42 +1,207 @@
+use crate::guid::Guid;
 use crate::item::{Cursor, DItem};
 use crate::item_factory::{ItemFactory};
-use crate::hashset::HashSetTable;
-use crate::tx_manager::TxManager;
+use crate::key::TableKey;
+use crate::hashset::{HashSetTable, StorageMode};
+use crate::index::{IndexExtractor, IndexValue, SecondaryIndex};
+use crate::mvcc::{next_revision, next_version, PinRegistry, SharedVersions, VersionStore};
+use crate::reflect::FieldValue;
+use crate::observer::{ChangeBatch, ChangeCallback, ChangeSource, ObserverList, Outbox, SubscriptionId};
+use crate::tx_delta_list::TxDeltaList;
+use crate::tx_manager::{next_group_id, TxManager};
 
-use std::sync::Mutex;
+use std::collections::HashMap;
+use std::ops::RangeBounds;
+use std::sync::{Arc, Mutex, RwLock};
 use crate::define::TxAction;
 
-pub struct Table {
+/// goto 결과: (undo 로 적용된 델타, redo 로 적용된 델타)
+pub type GotoDeltas<K = i32> = (Vec<TxDeltaList<K>>, Vec<TxDeltaList<K>>);
+
+/// GUID → (table_type, 키): 세션의 테이블이 함께 쓰는 색인 (테이블 단독이면 자기 항목만)
+pub type GuidRegistry<K = i32> = Arc<RwLock<HashMap<Guid, (u16, K)>>>;
+
+/// 키 타입 K 의 테이블 (세션, WAL, 스냅샷 파일은 i32 키 테이블만 다룸)
+pub struct Table<K: TableKey = i32> {
     pub table_type: u16,
     pub item_type: u16,
-    pub items: HashSetTable,
-    pub tx: TxManager,
+    pub items: HashSetTable<K>,
+    pub tx: TxManager<K>,
+    observers: ObserverList,
+    versions: SharedVersions<K>, // 커밋된 값의 버전 (스냅샷 읽기용)
+    indexes: HashMap<String, SecondaryIndex<K>>, // 이름 → 보조 인덱스
+    guids: GuidRegistry<K>,                      // GUID → (테이블, 키) (GUID 가 있는 아이템만)
+    guid_keys: HashMap<K, Guid>,                 // 키 → GUID (갱신 시 이전 값 제거용)
 }
 
-impl Table {
+impl<K: TableKey> Table<K> {
     pub fn new(table_type: u16, item_type: u16) -> Self {
+        Self::with_storage(table_type, item_type, StorageMode::Hash)
+    }
+
+    /// 저장 방식을 지정해 생성 (Ordered = 키 순 반복, 범위 조회, 페이지)
+    pub fn with_storage(table_type: u16, item_type: u16, mode: StorageMode) -> Self {
         Table {
             table_type,
             item_type,
-            items: HashSetTable::new(table_type, item_type),
+            items: HashSetTable::with_mode(table_type, item_type, mode),
             tx: TxManager::new(),
+            observers: ObserverList::new(),
+            versions: Arc::new(RwLock::new(VersionStore::default())),
+            indexes: HashMap::new(),
+            guids: Arc::new(RwLock::new(HashMap::new())),
+            guid_keys: HashMap::new(),
         }
     }
 
-    /// 아이템 삽입
-    pub fn insert(&mut self, key: i32, factory: &Mutex<ItemFactory>) -> Option<Cursor> {
-        let factory = factory.lock().ok()?;
-        let item = factory.create_item(self.item_type, key)?;
-        let cursor = Cursor::new(item);
+    pub fn storage_mode(&self) -> StorageMode {
+        self.items.mode()
+    }
+
+    /// 저장 방식 변경 (파일에서 불러온 테이블은 Hash 로 열림)
+    pub fn set_storage_mode(&mut self, mode: StorageMode) {
+        self.items.set_mode(mode);
+    }
+
+    /// 변경 구독: 커밋, undo, redo 마다 한 묶음씩 전달
+    pub fn subscribe(&mut self, callback: ChangeCallback) -> SubscriptionId {
+        self.observers.subscribe(None, callback)
+    }
+
+    pub fn unsubscribe(&mut self, id: SubscriptionId) -> bool {
+        self.observers.unsubscribe(id)
+    }
+
+    /// 테이블 단독 커밋 (구독자에게 알림)
+    pub fn commit(&mut self) {
+        self.commit_labelled("");
+    }
+
+    pub fn commit_labelled(&mut self, label: &str) {
+        if self.tx.current_count() > 0 {
+            self.commit_group(next_group_id(), label, next_version());
+        }
+    }
+
+    /// 지정한 그룹과 커밋 버전으로 커밋 (세션 커밋은 여러 테이블이 같은 그룹과 버전을 공유)
+    pub fn commit_group(&mut self, group: u64, label: &str, version: u64) -> bool {
+        if !self.tx.commit_group(group, label) {
+            return false;
+        }
+        if let Some(delta) = self.tx.last_committed() {
+            let keys: Vec<K> = delta.keys.iter().cloned().collect();
+            self.record_versions(&keys, version);
+        }
+        self.notify_committed();
+        true
+    }
+
+    /// 기록에서 버려져 복원할 수 없게 된 아이템을 factory 의 destroy 콜백으로 해제 (테이블에 남아 있는 것은 제외)
+    /// 해제할 아이템은 기록 한도로 오래된 커밋을 버리거나 새 커밋으로 redo 경로가 사라질 때 생김
+    pub fn release_items(&mut self, factory: &ItemFactory) -> usize {
+        let mut count = 0;
+        for item in self.tx.take_released() {
+            let live = match K::of(item.as_ref()) {
+                Some(key) => self.items.find(key).is_some_and(|cursors| cursors.iter().any(|c| Arc::ptr_eq(&c.data, &item))),
+                None => true, // 키를 알 수 없으면 해제하지 않음
+            };
+            if !live {
+                factory.destroy_item(item);
+                count += 1;
+            }
+        }
+        count
+    }
+
+    /// 커밋된 키의 현재 값을 version 으로 기록
+    pub fn record_versions(&mut self, keys: &[K], version: u64) {
+        let mut store = self.versions.write().unwrap();
+        for key in keys {
+            if let Some(cursors) = self.items.find_mut(key.clone()) {
+                for cursor in cursors.iter_mut().filter(|c| c.visible) {
+                    cursor.version = version;
+                }
+            }
+            store.record(key.clone(), version, self.items.find_visible(key.clone()).cloned());
+        }
+    }
+
+    /// 저장소 전체를 하나의 버전으로 기록 (스냅샷 파일에서 불러온 직후)
+    pub fn sync_versions(&mut self, version: u64) {
+        let keys: Vec<K> = self.items.keys();
+        self.record_versions(&keys, version);
+    }
+
+    /// 마지막으로 커밋된 값 (커밋되지 않은 변경은 무시)
+    pub fn committed(&self, key: K) -> Option<Cursor> {
+        self.versions.read().unwrap().get(key, u64::MAX).cloned()
+    }
+
+    /// 버전 저장소 (스냅샷 뷰가 공유)
+    pub fn versions(&self) -> SharedVersions<K> {
+        self.versions.clone()
+    }
+
+    /// 세션의 스냅샷 고정 목록을 사용 (세션에 등록할 때)
+    pub fn set_pin_registry(&mut self, pins: PinRegistry) {
+        self.versions = Arc::new(RwLock::new(VersionStore::new(pins)));
+    }
+
+    /// 세션 전체의 GUID 색인을 사용 (세션에 등록할 때, 다른 테이블과 GUID 중복 검사)
+    pub fn set_guid_registry(&mut self, guids: GuidRegistry<K>) {
+        {
+            let mut shared = guids.write().unwrap();
+            for (key, guid) in &self.guid_keys {
+                shared.insert(guid.clone(), (self.table_type, key.clone()));
+            }
+        }
+        self.guids = guids;
+    }
+
+    /// 알림을 바로 호출하지 않고 outbox 에 모음 (세션에 등록할 때)
+    pub fn set_outbox(&mut self, outbox: Option<Outbox>) {
+        self.observers.set_outbox(outbox);
+    }
+
+    /// 방금 커밋된 델타를 구독자에게 알림
+    pub fn notify_committed(&self) {
+        if let Some(delta) = self.tx.last_committed() {
+            self.notify(ChangeSource::Commit, delta);
+        }
+    }
+
+    /// 저장소에 적용된 방향의 델타를 구독자에게 알림
+    fn notify(&self, source: ChangeSource, delta: &TxDeltaList<K>) {
+        if self.observers.is_empty() {
+            return;
+        }
+        let mut batch = ChangeBatch::new(source, delta.group, &delta.label);
+        batch.add_delta(self.table_type, delta);
+        self.observers.notify(&batch);
+    }
 
+    /// 만든 아이템 삽입 (타입이 다르거나 이미 있는 키, 다른 아이템이 가진 GUID 는 실패)
+    pub fn insert_item(&mut self, item: Arc<dyn DItem>) -> Option<Cursor> {
+        if item.item_type() != self.item_type {
+            return None;
+        }
+        let key = K::of(item.as_ref())?;
+        if self.items.find_visible(key.clone()).is_some() || self.guid_taken(&key, item.as_ref()) {
+            return None;
+        }
+
+        let mut cursor = Cursor::new(item);
+        cursor.revision = next_revision(); // 삭제 후 다시 넣어도 삭제 전 값과 구분
         self.items.insert(cursor.clone());
+        self.reindex(key);
         self.tx.add(TxAction::Insert(cursor.clone())); // undo 시 삭제
         Some(cursor)
     }
 
     /// 아이템 삭제
-    pub fn remove(&mut self, key: i32) -> bool {
-        if let Some(cursors) = self.items.remove(key) {
+    pub fn remove(&mut self, key: K) -> bool {
+        if let Some(cursors) = self.items.remove(key.clone()) {
+            self.reindex(key);
             for cursor in cursors {
                 self.tx.add(TxAction::Remove(cursor)); // undo 시 복원
             }
@@ -46,60 +211,302 @@ impl Table {
         }
     }
 
+    /// 아이템 수정: 같은 키의 새 아이템으로 교체하고 이전/이후 커서를 모두 기록
+    pub fn modify(&mut self, key: K, new_item: Arc<dyn DItem>) -> Option<Cursor> {
+        if K::of(new_item.as_ref()).as_ref() != Some(&key)
+            || new_item.item_type() != self.item_type
+            || self.guid_taken(&key, new_item.as_ref())
+        {
+            return None;
+        }
+        let before = self.items.find_visible(key.clone())?.clone();
+
+        let mut after = Cursor::new(new_item);
+        after.param_data = before.param_data;
+        after.param = before.param;
+        after.revision = next_revision();
+
+        self.items.replace(after.clone());
+        self.reindex(key);
+        self.tx.add(TxAction::Modify {
+            before,
+            after: after.clone(),
+        }); // undo 시 이전 커서로 교체
+        Some(after)
+    }
+
     /// 아이템 조회
-    pub fn get(&self, key: i32) -> Option<&Cursor> {
+    pub fn get(&self, key: K) -> Option<&Cursor> {
         self.items.find_visible(key)
     }
 
+    /// 이름으로 필드 읽기 (스키마가 등록된 타입만)
+    pub fn get_field(&self, key: K, name: &str, factory: &Mutex<ItemFactory>) -> Option<FieldValue> {
+        let item = self.get(key)?.data.clone();
+        factory.lock().ok()?.get_field(item.as_ref(), name)
+    }
+
+    /// 이름으로 필드 쓰기 (modify 와 같이 undo 가능)
+    pub fn set_field(&mut self, key: K, name: &str, value: FieldValue, factory: &Mutex<ItemFactory>) -> Option<Cursor> {
+        let item = self.get(key.clone())?.data.clone();
+        let new_item = factory.lock().ok()?.set_field(item.as_ref(), name, value)?;
+        self.modify(key, new_item)
+    }
+
     /// 전체 초기화
     pub fn clear(&mut self) {
         self.items.clear();
         self.tx.clear();
+        for index in self.indexes.values_mut() {
+            index.clear();
+        }
+        self.clear_guids();
+        self.record_clear();
     }
 
-    /// Undo
-    pub fn undo(&mut self) {
-        if let Some(mut delta) = self.tx.undo() {
-            for action in delta.iter_mut() {
-                match action {
-                    TxAction::Insert(cursor) => {
-                        self.items.insert(cursor.clone()); // 삭제 취소
-                        *action = TxAction::Cancelled;
-                    }
-                    TxAction::Remove(cursor) => {
-                        self.items.remove(cursor.key()); // 삽입 취소
-                        *action = TxAction::Cancelled;
-                    }
-                    TxAction::Modify { before, .. } => {
-                        self.items.insert(before.clone()); // 수정 취소
-                        *action = TxAction::Cancelled;
-                    }
-                    TxAction::Cancelled => {}
+    /// 저장소를 비운 것을 새 버전으로 기록 (이미 만든 스냅샷은 이전 값을 그대로 봄)
+    fn record_clear(&mut self) {
+        let keys = {
+            let store = self.versions.read().unwrap();
+            store.keys(u64::MAX)
+        };
+        if !keys.is_empty() {
+            self.record_versions(&keys, next_version());
+        }
+    }
+
+    /// 액션을 저장소에 적용 (redo, 로그 재생)
+    pub fn apply_action(&mut self, action: &TxAction) {
+        match action {
+            TxAction::Insert(cursor) => {
+                self.items.replace(cursor.clone()); // 같은 키가 남아 있으면 교체
+            }
+            TxAction::Remove(cursor) => {
+                if let Some(key) = cursor.key_as::<K>() {
+                    self.items.remove(key);
                 }
             }
+            TxAction::Modify { after, .. } => {
+                self.items.replace(after.clone());
+            }
+            TxAction::Cancelled => {}
+        }
+        if let Some(key) = action.key_as::<K>() {
+            self.reindex(key);
         }
     }
 
-    /// Redo
-    pub fn redo(&mut self) {
-        if let Some(mut delta) = self.tx.redo() {
-            for action in delta.iter_mut() {
-                match action {
-                    TxAction::Insert(cursor) => {
-                        self.items.remove(cursor.key()); // 다시 삭제
-                        *action = TxAction::Cancelled;
-                    }
-                    TxAction::Remove(cursor) => {
-                        self.items.insert(cursor.clone()); // 다시 삽입
-                        *action = TxAction::Cancelled;
-                    }
-                    TxAction::Modify { after, .. } => {
-                        self.items.insert(after.clone()); // 다시 수정
-                        *action = TxAction::Cancelled;
-                    }
-                    TxAction::Cancelled => {}
-                }
+    /// 보조 인덱스 등록: 현재 아이템으로 채운 뒤 이후 변경(undo/redo 포함)마다 갱신
+    pub fn create_index(&mut self, name: &str, extractor: IndexExtractor) -> bool {
+        if self.indexes.contains_key(name) {
+            return false;
+        }
+        let mut index = SecondaryIndex::new(name, extractor);
+        for cursor in self.items.all_items().filter(|c| c.visible) {
+            if let Some(key) = cursor.key_as::<K>() {
+                index.update(key, Some(cursor.data.as_ref()));
+            }
+        }
+        self.indexes.insert(name.to_string(), index);
+        true
+    }
+
+    pub fn drop_index(&mut self, name: &str) -> bool {
+        self.indexes.remove(name).is_some()
+    }
+
+    pub fn index(&self, name: &str) -> Option<&SecondaryIndex<K>> {
+        self.indexes.get(name)
+    }
+
+    /// 인덱스 값이 같은 아이템 (키 순)
+    pub fn find_by_index(&self, name: &str, value: &IndexValue) -> Vec<&Cursor> {
+        let Some(index) = self.indexes.get(name) else {
+            return Vec::new();
+        };
+        index.find(value).into_iter().filter_map(|key| self.get(key)).collect()
+    }
+
+    /// 인덱스 값이 범위에 드는 아이템 (값 순)
+    pub fn range_by_index<R: RangeBounds<IndexValue>>(&self, name: &str, range: R) -> Vec<&Cursor> {
+        let Some(index) = self.indexes.get(name) else {
+            return Vec::new();
+        };
+        index.range(range).into_iter().filter_map(|key| self.get(key)).collect()
+    }
+
+    /// 키의 현재 아이템으로 모든 보조 인덱스 갱신
+    fn reindex(&mut self, key: K) {
+        let item = self.items.find_visible(key.clone()).map(|c| c.data.clone());
+        self.update_guid(key.clone(), item.as_deref().and_then(|item| item.guid()));
+        for index in self.indexes.values_mut() {
+            index.update(key.clone(), item.as_deref());
+        }
+    }
+
+    fn update_guid(&mut self, key: K, guid: Option<Guid>) {
+        if self.guid_keys.get(&key) == guid.as_ref() {
+            return;
+        }
+        let entry = (self.table_type, key.clone());
+        let mut guids = self.guids.write().unwrap();
+        if let Some(old) = self.guid_keys.remove(&key)
+            && guids.get(&old) == Some(&entry)
+        {
+            guids.remove(&old);
+        }
+        if let Some(guid) = guid {
+            // insert/modify 가 중복을 거부하므로 먼저 가진 아이템을 유지
+            guids.entry(guid.clone()).or_insert(entry);
+            self.guid_keys.insert(key, guid);
+        }
+    }
+
+    /// 키가 아닌 다른 아이템(다른 테이블 포함)이 item 의 GUID 를 이미 가졌는지
+    fn guid_taken(&self, key: &K, item: &dyn DItem) -> bool {
+        let Some(guid) = item.guid() else {
+            return false;
+        };
+        self.guids
+            .read()
+            .unwrap()
+            .get(&guid)
+            .is_some_and(|(table_type, owner)| *table_type != self.table_type || owner != key)
+    }
+
+    /// 이 테이블의 GUID 항목만 색인에서 제거
+    fn clear_guids(&mut self) {
+        let mut guids = self.guids.write().unwrap();
+        for (key, guid) in self.guid_keys.drain() {
+            if guids.get(&guid) == Some(&(self.table_type, key)) {
+                guids.remove(&guid);
             }
         }
     }
+
+    /// 저장소에 직접 넣은 항목(스냅샷 복원 등)으로 GUID 와 보조 인덱스를 다시 만듦
+    pub fn rebuild_indexes(&mut self) {
+        self.clear_guids();
+        for index in self.indexes.values_mut() {
+            index.clear();
+        }
+        for key in self.items.keys() {
+            self.reindex(key);
+        }
+    }
+
+    /// GUID 로 보이는 아이템 찾기
+    pub fn find_by_guid(&self, guid: &Guid) -> Option<&Cursor> {
+        let (table_type, key) = self.guids.read().unwrap().get(guid)?.clone();
+        if table_type != self.table_type {
+            return None;
+        }
+        self.get(key)
+    }
+
+    pub fn guid_count(&self) -> usize {
+        self.guid_keys.len()
+    }
+
+    /// 액션을 저장소에서 되돌림 (undo)
+    pub fn revert_action(&mut self, action: &TxAction) {
+        self.apply_action(&action.inverse());
+    }
+
+    /// 커밋되지 않은 변경을 되돌려 저장된 델타 시점으로 복원
+    pub fn rollback_current_to(&mut self, saved: &TxDeltaList<K>) {
+        let current = self.tx.take_current();
+        for action in current.actions.iter().rev() {
+            self.revert_action(action);
+        }
+        for action in saved.iter() {
+            self.apply_action(action);
+        }
+        self.tx.set_current(saved.clone());
+    }
+
+    /// Undo: 되돌린 델타 반환
+    pub fn undo(&mut self) -> Option<TxDeltaList<K>> {
+        let delta = self.tx.undo()?;
+        for action in delta.actions.iter().rev() {
+            self.revert_action(action);
+        }
+        let keys: Vec<K> = delta.keys.iter().cloned().collect();
+        self.record_versions(&keys, next_version());
+        self.notify(ChangeSource::Undo, &delta.inverse());
+        Some(delta)
+    }
+
+    /// 적용된 커밋이 position 개가 될 때까지 undo (되돌린 수 반환)
+    pub fn undo_to(&mut self, position: usize) -> usize {
+        let mut count = 0;
+        while self.tx.position() > position && self.undo().is_some() {
+            count += 1;
+        }
+        count
+    }
+
+    /// 적용된 커밋이 position 개가 될 때까지 redo (다시 적용한 수 반환)
+    pub fn redo_to(&mut self, position: usize) -> usize {
+        let mut count = 0;
+        while self.tx.position() < position && self.redo().is_some() {
+            count += 1;
+        }
+        count
+    }
+
+    /// undo 트리의 임의 노드로 이동: 공통 조상까지 undo 후 대상까지 redo
+    /// (undo 로 적용된 변경, redo 로 적용된 변경)을 저장소에 적용된 방향으로 반환
+    /// 트리 모드가 아니거나 노드가 없으면 None
+    pub fn goto(&mut self, group: u64) -> Option<GotoDeltas<K>> {
+        if !self.tx.is_tree_mode() {
+            return None;
+        }
+        let path = self.tx.path_to(group)?;
+        let applied = self.tx.applied_groups();
+        let common = applied.iter().zip(&path).take_while(|(a, b)| a == b).count();
+
+        let mut undone = Vec::new();
+        while self.tx.position() > common {
+            match self.undo() {
+                Some(delta) => undone.push(delta.inverse()),
+                None => break,
+            }
+        }
+        self.tx.set_redo_path(&path[common..]);
+        let mut redone = Vec::new();
+        while self.tx.position() < path.len() {
+            match self.redo() {
+                Some(delta) => redone.push(delta),
+                None => break,
+            }
+        }
+        // 이후 redo 는 가장 최근 가지를 따라감
+        let future = self.tx.latest_path_from(group);
+        self.tx.set_redo_path(&future);
+        Some((undone, redone))
+    }
+
+    /// Redo: 다시 적용한 델타 반환
+    pub fn redo(&mut self) -> Option<TxDeltaList<K>> {
+        let delta = self.tx.redo()?;
+        for action in delta.iter() {
+            self.apply_action(action);
+        }
+        let keys: Vec<K> = delta.keys.iter().cloned().collect();
+        self.record_versions(&keys, next_version());
+        self.notify(ChangeSource::Redo, &delta);
+        Some(delta)
+    }
+}
+
+impl Table {
+    /// ItemFactory 로 키의 새 아이템을 만들어 삽입 (이미 있는 키는 실패)
+    pub fn insert(&mut self, key: i32, factory: &Mutex<ItemFactory>) -> Option<Cursor> {
+        if self.items.find_visible(key).is_some() {
+            return None;
+        }
+        let item = factory.lock().ok()?.create_item(self.item_type, key)?;
+        self.insert_item(item)
+    }
 }
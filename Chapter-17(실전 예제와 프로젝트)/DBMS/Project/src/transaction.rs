@@ -1,35 +1,191 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::io;
+use crate::item::Cursor;
 use crate::session::Session;
+use crate::tx_delta_list::TxDeltaList;
+
+type PendingState = HashMap<u16, TxDeltaList>;
+type ReadSet = HashMap<(u16, i32), Option<u64>>; // 읽은 키 → 커밋된 revision
+
+/// 커밋 실패
+#[derive(Debug)]
+pub enum TxError {
+    /// 읽은 뒤 다른 커밋이 같은 키를 바꿈 (revision: None = 없는 키)
+    Conflict {
+        table_type: u16,
+        key: i32,
+        expected: Option<u64>,
+        actual: Option<u64>,
+    },
+    Io(io::Error),
+}
+
+impl fmt::Display for TxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TxError::Conflict { table_type, key, expected, actual } => write!(
+                f,
+                "commit conflict on table {} key {}: read revision {:?}, now {:?}",
+                table_type, key, expected, actual
+            ),
+            TxError::Io(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for TxError {}
+
+impl From<io::Error> for TxError {
+    fn from(e: io::Error) -> Self {
+        TxError::Io(e)
+    }
+}
+
+impl From<TxError> for io::Error {
+    fn from(e: TxError) -> Self {
+        match e {
+            TxError::Io(e) => e,
+            conflict => io::Error::other(conflict),
+        }
+    }
+}
 
 pub struct Transaction<'a> {
     session: &'a mut Session,
     committed: bool,
+    base: PendingState,                     // 시작 시점 (롤백하면 이 상태로 복원)
+    savepoints: Vec<(String, PendingState)>, // 이름 → 시점
+    reads: ReadSet,
+    parent_reads: Option<&'a mut ReadSet>, // 중첩 커밋 시 읽은 키를 넘길 부모의 목록
 }
 
 impl<'a> Transaction<'a> {
+    /// 최상위 트랜잭션 시작 (시작 전에 있던 커밋되지 않은 변경은 롤백해도 남음)
     pub fn new(session: &'a mut Session) -> Self {
+        let base = session.pending_state();
         Transaction {
             session,
             committed: false,
+            base,
+            savepoints: Vec::new(),
+            reads: HashMap::new(),
+            parent_reads: None,
+        }
+    }
+
+    /// 트랜잭션 안에서 세션 사용
+    pub fn session(&mut self) -> &mut Session {
+        self.session
+    }
+
+    /// 중첩 트랜잭션 시작: 커밋하면 변경과 읽은 키가 부모에 합쳐지고, 롤백하면 시작 시점으로 복원
+    pub fn begin_nested(&mut self) -> Transaction<'_> {
+        let base = self.session.pending_state();
+        Transaction {
+            session: &mut *self.session,
+            committed: false,
+            base,
+            savepoints: Vec::new(),
+            reads: HashMap::new(),
+            parent_reads: Some(&mut self.reads),
         }
     }
 
-    /// 명시적 커밋
-    pub fn commit(mut self) {
-        self.session.clear_all(); // 트랜잭션 반영 후 초기화
+    pub fn is_nested(&self) -> bool {
+        self.parent_reads.is_some()
+    }
+
+    /// 세이브포인트 생성 (같은 이름이면 새 시점으로 덮어씀)
+    pub fn savepoint(&mut self, name: &str) {
+        self.savepoints.retain(|(n, _)| n != name);
+        self.savepoints.push((name.to_string(), self.session.pending_state()));
+    }
+
+    /// 세이브포인트 시점으로 되돌림 (이후 세이브포인트는 제거)
+    pub fn rollback_to(&mut self, name: &str) -> bool {
+        let Some(pos) = self.savepoints.iter().position(|(n, _)| n == name) else {
+            return false;
+        };
+        self.savepoints.truncate(pos + 1);
+        self.session.rollback_pending_to(&self.savepoints[pos].1);
+        true
+    }
+
+    /// 세이브포인트 해제 (변경은 유지)
+    pub fn release(&mut self, name: &str) -> bool {
+        let Some(pos) = self.savepoints.iter().position(|(n, _)| n == name) else {
+            return false;
+        };
+        self.savepoints.truncate(pos);
+        true
+    }
+
+    /// 아이템 조회 (이 트랜잭션의 변경 포함), 커밋 시 검사할 revision 기록
+    pub fn read(&mut self, table_type: u16, key: i32) -> Option<Cursor> {
+        let table = self.session.get_table(table_type)?;
+        let committed = table.committed(key).map(|c| c.revision);
+        self.reads.entry((table_type, key)).or_insert(committed);
+        table.get(key).cloned()
+    }
+
+    /// 다른 곳에서 읽은 revision 을 커밋 시 검사하도록 등록 (None = 없는 키였음)
+    pub fn expect_revision(&mut self, table_type: u16, key: i32, revision: Option<u64>) {
+        self.reads.insert((table_type, key), revision);
+    }
+
+    /// 읽은 키가 그 뒤로 다른 커밋에 의해 바뀌었는지 검사
+    pub fn validate(&self) -> Result<(), TxError> {
+        for (&(table_type, key), &expected) in &self.reads {
+            let actual = self
+                .session
+                .get_table(table_type)
+                .and_then(|t| t.committed(key))
+                .map(|c| c.revision);
+            if actual != expected {
+                return Err(TxError::Conflict { table_type, key, expected, actual });
+            }
+        }
+        Ok(())
+    }
+
+    /// 명시적 커밋: 최상위는 로그에 기록하고 undo 스택에 반영, 중첩은 부모에 합침
+    /// 읽은 키가 바뀌었으면 Conflict 로 실패하고 변경은 롤백됨
+    pub fn commit(self) -> Result<(), TxError> {
+        self.commit_labelled("")
+    }
+
+    /// 설명을 붙여 커밋 (중첩 트랜잭션은 부모에 합쳐지므로 설명 무시)
+    /// 세션 커밋이 실패해도 (로그 기록, 참조 검사) 변경은 롤백됨
+    pub fn commit_labelled(mut self, label: &str) -> Result<(), TxError> {
+        self.validate()?;
+        if let Some(parent) = self.parent_reads.as_mut() {
+            // 부모 커밋 때 다시 검사 (부모가 먼저 읽은 키는 그 revision 유지)
+            for (key, revision) in self.reads.drain() {
+                parent.entry(key).or_insert(revision);
+            }
+        } else {
+            self.session.commit_labelled(label)?;
+        }
         self.committed = true;
+        Ok(())
     }
 
     /// 명시적 롤백
     pub fn rollback(mut self) {
-        self.session.undo_all();
         self.committed = true;
+        self.rollback_pending();
+    }
+
+    fn rollback_pending(&mut self) {
+        self.session.rollback_pending_to(&self.base);
     }
 }
 
 impl<'a> Drop for Transaction<'a> {
     fn drop(&mut self) {
         if !self.committed {
-            self.session.undo_all(); // 자동 롤백
+            self.rollback_pending(); // 자동 롤백
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,217 @@
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use crate::item::DItem;
+    use crate::session::Session;
+    use crate::item_factory::item_factory_mut;
+    use crate::reference::Reference;
+    use crate::transaction::{Transaction, TxError};
+
+    #[derive(Debug)]
+    struct TxItem {
+        key: i32,
+    }
+
+    impl DItem for TxItem {
+        fn key(&self) -> i32 { self.key }
+        fn item_type(&self) -> u16 { 400 }
+        fn table_type(&self) -> u16 { 40 }
+        fn serialize(&self, _stream: &mut dyn crate::tx_stream::TxStream, _session: &Session) {}
+    }
+
+    fn new_session() -> Session {
+        item_factory_mut().lock().unwrap().register_type(
+            400,
+            40,
+            Arc::new(|key| Arc::new(TxItem { key })),
+            Arc::new(|_item| {}),
+        );
+        let mut session = Session::new();
+        session.register_table(40, 400);
+        session
+    }
+
+    #[test]
+    fn test_savepoint_rollback_to() {
+        let factory = item_factory_mut();
+        let mut session = new_session();
+
+        let mut tx = Transaction::new(&mut session);
+        let table = tx.session().get_table_mut(40).unwrap();
+        table.insert(1, factory);
+        tx.savepoint("first");
+
+        let table = tx.session().get_table_mut(40).unwrap();
+        table.insert(2, factory);
+        table.remove(1);
+        tx.savepoint("second");
+        tx.session().get_table_mut(40).unwrap().insert(3, factory);
+
+        assert!(tx.rollback_to("first"));
+        assert!(!tx.rollback_to("second")); // 이후 세이브포인트는 제거됨
+        let table = tx.session().get_table(40).unwrap();
+        assert!(table.get(1).is_some());
+        assert!(table.get(2).is_none());
+        assert!(table.get(3).is_none());
+        tx.commit().unwrap();
+
+        // 커밋된 내용은 한 번의 undo 로 되돌아감
+        let table = session.get_table(40).unwrap();
+        assert!(table.get(1).is_some());
+        session.undo_all().unwrap();
+        assert!(session.get_table(40).unwrap().get(1).is_none());
+    }
+
+    #[test]
+    fn test_nested_transaction_commit_and_rollback() {
+        let factory = item_factory_mut();
+        let mut session = new_session();
+        session.get_table_mut(40).unwrap().insert(10, factory);
+        session.commit_all().unwrap();
+
+        {
+            let mut tx = Transaction::new(&mut session);
+            tx.session().get_table_mut(40).unwrap().insert(1, factory);
+
+            {
+                // 실패한 하위 단계: 자동 롤백
+                let mut child = tx.begin_nested();
+                child.session().get_table_mut(40).unwrap().insert(2, factory);
+                child.session().get_table_mut(40).unwrap().remove(10);
+            }
+            {
+                let mut child = tx.begin_nested();
+                child.session().get_table_mut(40).unwrap().insert(3, factory);
+                child.commit().unwrap(); // 부모에 합쳐짐
+            }
+
+            let table = tx.session().get_table(40).unwrap();
+            assert!(table.get(10).is_some());
+            assert!(table.get(2).is_none());
+            assert!(table.get(3).is_some());
+            assert_eq!(table.tx.current_count(), 2);
+            // 최상위 롤백 생략 → Drop 에서 커밋되지 않은 변경만 취소
+        }
+
+        let table = session.get_table(40).unwrap();
+        assert!(table.get(10).is_some());
+        assert!(table.get(1).is_none());
+        assert!(table.get(3).is_none());
+        assert!(table.tx.has_undo());
+    }
+
+    #[test]
+    fn test_rollback_keeps_changes_made_before_begin() {
+        let factory = item_factory_mut();
+        let mut session = new_session();
+        session.get_table_mut(40).unwrap().insert(1, factory); // 트랜잭션 전 변경 (커밋 전)
+
+        {
+            let mut tx = Transaction::new(&mut session);
+            tx.session().get_table_mut(40).unwrap().insert(2, factory);
+            tx.session().get_table_mut(40).unwrap().remove(1);
+            // Drop → 시작 시점으로 롤백
+        }
+        let table = session.get_table(40).unwrap();
+        assert!(table.get(1).is_some());
+        assert!(table.get(2).is_none());
+
+        let mut tx = Transaction::new(&mut session);
+        tx.session().get_table_mut(40).unwrap().insert(3, factory);
+        tx.rollback();
+        assert_eq!(session.get_table(40).unwrap().tx.current_count(), 1);
+    }
+
+    #[test]
+    fn test_commit_detects_lost_update() {
+        let factory = item_factory_mut();
+        let mut session = new_session();
+        session.get_table_mut(40).unwrap().insert(1, factory);
+        session.commit_all().unwrap();
+
+        // 자신의 변경은 충돌이 아님
+        let mut tx = Transaction::new(&mut session);
+        let inserted = tx.read(40, 1).unwrap().revision;
+        tx.session().get_table_mut(40).unwrap().modify(1, Arc::new(TxItem { key: 1 }));
+        assert!(tx.read(40, 1).unwrap().revision > inserted);
+        tx.commit().unwrap();
+
+        // 동기화 계층이 revision 을 본 뒤 다른 편집자가 먼저 수정
+        let seen = session.get_table(40).unwrap().get(1).unwrap().revision;
+        session.get_table_mut(40).unwrap().modify(1, Arc::new(TxItem { key: 1 }));
+        session.commit_all().unwrap();
+        let current = session.get_table(40).unwrap().get(1).unwrap().revision;
+
+        let mut tx = Transaction::new(&mut session);
+        tx.expect_revision(40, 1, Some(seen));
+        tx.session().get_table_mut(40).unwrap().remove(1);
+        match tx.commit() {
+            Err(TxError::Conflict { key, expected, actual, .. }) => {
+                assert_eq!(key, 1);
+                assert_eq!(expected, Some(seen));
+                assert_eq!(actual, Some(current));
+            }
+            other => panic!("expected conflict, got {:?}", other),
+        }
+        // 실패한 커밋의 변경은 롤백됨
+        assert_eq!(session.get_table(40).unwrap().get(1).unwrap().revision, current);
+        assert_eq!(session.get_table(40).unwrap().tx.current_count(), 0);
+    }
+
+    #[test]
+    fn test_modify_after_undo_gets_a_new_revision() {
+        let factory = item_factory_mut();
+        let mut session = new_session();
+        session.get_table_mut(40).unwrap().insert(1, factory);
+        session.commit_all().unwrap();
+
+        // 수정 → undo → 다시 수정: 처음 수정한 값을 읽은 트랜잭션은 충돌 (ABA)
+        session.get_table_mut(40).unwrap().modify(1, Arc::new(TxItem { key: 1 }));
+        session.commit_all().unwrap();
+        let seen = session.get_table(40).unwrap().get(1).unwrap().revision;
+        session.undo_all().unwrap();
+        session.get_table_mut(40).unwrap().modify(1, Arc::new(TxItem { key: 1 }));
+        session.commit_all().unwrap();
+        assert_ne!(session.get_table(40).unwrap().get(1).unwrap().revision, seen);
+
+        let mut tx = Transaction::new(&mut session);
+        tx.expect_revision(40, 1, Some(seen));
+        assert!(matches!(tx.validate(), Err(TxError::Conflict { key: 1, .. })));
+    }
+
+    #[test]
+    fn test_nested_reads_and_reinserts_are_validated() {
+        let factory = item_factory_mut();
+        let mut session = new_session();
+        session.get_table_mut(40).unwrap().insert(1, factory);
+        session.get_table_mut(40).unwrap().insert(2, factory);
+        session.commit_all().unwrap();
+
+        // 중첩 트랜잭션에서만 읽은 키도 부모 커밋 때 검사
+        let mut tx = Transaction::new(&mut session);
+        let mut nested = tx.begin_nested();
+        assert!(nested.read(40, 1).is_some());
+        nested.commit().unwrap();
+        tx.session().get_table_mut(40).unwrap().modify(1, Arc::new(TxItem { key: 1 }));
+        tx.session().commit_all().unwrap(); // 다른 편집자의 커밋 흉내
+        assert!(matches!(tx.commit(), Err(TxError::Conflict { key: 1, .. })));
+
+        // 삭제 후 다시 넣은 아이템은 이전 revision 과 다름
+        let table = session.get_table_mut(40).unwrap();
+        let removed = table.get(2).unwrap().revision;
+        table.remove(2);
+        table.insert(2, factory);
+        session.commit_all().unwrap();
+        let mut tx = Transaction::new(&mut session);
+        tx.expect_revision(40, 2, Some(removed));
+        assert!(matches!(tx.commit(), Err(TxError::Conflict { key: 2, actual: Some(actual), .. }) if actual != removed));
+
+        // 세션 커밋이 실패해도 변경은 롤백됨
+        assert!(session.add_reference(Reference::new("parent", (40, 400), (40, 400), |item| (item.key() == 5).then_some(99))));
+        let mut tx = Transaction::new(&mut session);
+        tx.session().get_table_mut(40).unwrap().insert(5, factory);
+        assert!(matches!(tx.commit(), Err(TxError::Io(_))));
+        assert!(session.get_table(40).unwrap().get(5).is_none());
+        assert_eq!(session.get_table(40).unwrap().tx.current_count(), 0);
+    }
+}
@@ -1,39 +1,94 @@
 use std::collections::HashSet;
+use std::time::SystemTime;
 use crate::define::TxAction;
 use crate::item::Cursor;
+use crate::key::TableKey;
 
-#[derive(Default, Clone)]
-pub struct TxDeltaList {
+/// 한 번의 커밋에 모인 액션 (같은 키의 액션은 키 타입 K 기준으로 하나로 병합)
+#[derive(Clone)]
+pub struct TxDeltaList<K = i32> {
     pub actions: Vec<TxAction>,
-    pub keys: HashSet<i32>,
+    pub keys: HashSet<K>,
+    pub group: u64, // 커밋 그룹 번호 (여러 테이블이 같은 번호를 공유, 0 = 미커밋)
+    pub label: String,                // 커밋 설명 (history 표시용)
+    pub timestamp: Option<SystemTime>, // 커밋 시각
 }
 
-impl TxDeltaList {
+impl<K: TableKey> Default for TxDeltaList<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: TableKey> TxDeltaList<K> {
     pub fn new() -> Self {
         TxDeltaList {
             actions: Vec::new(),
             keys: HashSet::new(),
+            group: 0,
+            label: String::new(),
+            timestamp: None,
         }
     }
 
-    /// TxAction 추가 (중복 키 방지)
+    /// TxAction 추가 (같은 키의 액션은 하나로 병합)
+    /// 델타는 커밋 전후의 순 변화만 담음: 처음 액션만 남기면 삽입 후 수정한 값이
+    /// redo 와 WAL 에서 사라지고, 삽입 후 삭제한 키가 undo 때 되살아남
     pub fn add(&mut self, action: TxAction) {
-        let key = match &action {
-            TxAction::Insert(c) => c.key(),
-            TxAction::Remove(c) => c.key(),
-            TxAction::Modify { after, .. } => after.key(),
-            TxAction::Cancelled => return, // 무시
+        let Some(key) = action.key_as::<K>() else {
+            return; // Cancelled 나 키 타입이 맞지 않는 액션 무시
         };
 
-        if self.keys.insert(key) {
+        if self.keys.insert(key.clone()) {
             self.actions.push(action);
+            return;
+        }
+
+        let Some(pos) = self.actions.iter().position(|a| a.key_as::<K>().as_ref() == Some(&key)) else {
+            return;
+        };
+        let prev = std::mem::replace(&mut self.actions[pos], TxAction::Cancelled);
+        match Self::merge(prev, action) {
+            Some(merged) => self.actions[pos] = merged,
+            None => {
+                // 삽입 후 삭제: 변경 없음
+                self.actions.remove(pos);
+                self.keys.remove(&key);
+            }
+        }
+    }
+
+    /// 같은 키에 대한 두 액션을 순서대로 합친 결과
+    fn merge(prev: TxAction, next: TxAction) -> Option<TxAction> {
+        match (prev, next) {
+            (TxAction::Insert(_), TxAction::Remove(_)) => None,
+            (TxAction::Insert(_), TxAction::Modify { after, .. }) => Some(TxAction::Insert(after)),
+            (TxAction::Remove(before), TxAction::Insert(after)) => Some(TxAction::Modify { before, after }),
+            (TxAction::Modify { before, .. }, TxAction::Modify { after, .. }) => Some(TxAction::Modify { before, after }),
+            (TxAction::Modify { before, .. }, TxAction::Remove(_)) => Some(TxAction::Remove(before)),
+            (prev, _) => Some(prev), // 잘못된 순서: 처음 액션 유지
         }
     }
 
+    /// 역방향 델타: 액션 순서를 뒤집고 각 액션을 반전
+    pub fn inverse(&self) -> TxDeltaList<K> {
+        let mut delta = TxDeltaList::new();
+        for action in self.actions.iter().rev() {
+            delta.add(action.inverse());
+        }
+        delta.group = self.group;
+        delta.label = self.label.clone();
+        delta.timestamp = self.timestamp;
+        delta
+    }
+
     /// 전체 초기화
     pub fn clear(&mut self) {
         self.actions.clear();
         self.keys.clear();
+        self.group = 0;
+        self.label.clear();
+        self.timestamp = None;
     }
 
     /// TxAction 수
@@ -41,6 +96,33 @@ impl TxDeltaList {
         self.actions.len()
     }
 
+    /// 대략적인 메모리 사용량 (바이트): 액션, 키, 설명, 참조하는 아이템 크기 포함
+    pub fn approx_bytes(&self) -> usize {
+        let mut bytes = std::mem::size_of::<TxDeltaList<K>>()
+            + self.actions.capacity() * std::mem::size_of::<TxAction>()
+            + self.keys.capacity() * std::mem::size_of::<K>()
+            + self.label.capacity();
+        for action in &self.actions {
+            bytes += match action {
+                TxAction::Insert(c) | TxAction::Remove(c) => std::mem::size_of_val(&*c.data),
+                TxAction::Modify { before, after } => {
+                    std::mem::size_of_val(&*before.data) + std::mem::size_of_val(&*after.data)
+                }
+                TxAction::Cancelled => 0,
+            };
+        }
+        bytes
+    }
+
+    /// 델타가 참조하는 모든 커서
+    pub fn cursors(&self) -> impl Iterator<Item = &Cursor> {
+        self.actions.iter().flat_map(|action| match action {
+            TxAction::Insert(c) | TxAction::Remove(c) => vec![c],
+            TxAction::Modify { before, after } => vec![before, after],
+            TxAction::Cancelled => vec![],
+        })
+    }
+
     /// 읽기 전용 반복자
     pub fn iter(&self) -> impl Iterator<Item = &TxAction> {
         self.actions.iter()
@@ -75,18 +157,18 @@ impl TxDeltaList {
     }
 
     /// 키로 커서 찾기
-    pub fn find_by_key(&self, key: i32) -> Option<&Cursor> {
+    pub fn find_by_key(&self, key: K) -> Option<&Cursor> {
         self.actions.iter().find_map(|action| {
             match action {
                 TxAction::Insert(c) | TxAction::Remove(c) => {
-                    if c.key() == key {
+                    if c.key_as::<K>().as_ref() == Some(&key) {
                         Some(c)
                     } else {
                         None
                     }
                 }
                 TxAction::Modify { after, .. } => {
-                    if after.key() == key {
+                    if after.key_as::<K>().as_ref() == Some(&key) {
                         Some(after)
                     } else {
                         None
@@ -1,37 +1,203 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::SystemTime;
 use crate::define::TxAction;
+use crate::item::DItem;
+use crate::key::TableKey;
 use crate::tx_delta_list::TxDeltaList;
 
-#[derive(Default, Clone)]
-pub struct TxManager {
-    undo_stack: Vec<TxDeltaList>,
-    redo_stack: Vec<TxDeltaList>,
-    current: TxDeltaList,
+static NEXT_GROUP: AtomicU64 = AtomicU64::new(1);
+
+/// 새 커밋 그룹 번호 (프로세스 전체에서 증가)
+pub fn next_group_id() -> u64 {
+    NEXT_GROUP.fetch_add(1, Ordering::Relaxed)
+}
+
+/// 커밋 기록 한 줄 (Edit > History 표시용)
+#[derive(Clone, Debug)]
+pub struct HistoryEntry {
+    pub group: u64,
+    pub label: String,
+    pub timestamp: Option<SystemTime>,
+    pub action_count: usize,
+    pub applied: bool, // false 면 undo 되어 redo 대기 중
+}
+
+/// undo 기록 한도 (None = 제한 없음)
+#[derive(Clone, Copy, Debug, Default)]
+pub struct HistoryLimit {
+    pub max_steps: Option<usize>, // 보관할 최대 커밋 수
+    pub max_bytes: Option<usize>, // 대략적인 최대 메모리 (바이트)
+}
+
+impl HistoryLimit {
+    pub fn is_exceeded(&self, steps: usize, bytes: usize) -> bool {
+        self.max_steps.is_some_and(|max| steps > max) || self.max_bytes.is_some_and(|max| bytes > max)
+    }
+}
+
+/// 아이템 식별용 주소 (같은 Arc 를 공유하는 커서는 같은 값)
+fn item_id(item: &Arc<dyn DItem>) -> usize {
+    Arc::as_ptr(item) as *const () as usize
+}
+
+/// undo 트리의 노드 (그룹 번호로 식별, 0 = 루트)
+#[derive(Clone)]
+pub struct UndoNode<K = i32> {
+    pub delta: TxDeltaList<K>,
+    pub parent: u64,
+    pub children: Vec<u64>,
+}
+
+/// undo 트리의 가지 하나 (끝 노드 기준)
+#[derive(Clone, Debug)]
+pub struct BranchInfo {
+    pub tip: u64,      // 가지 끝 노드
+    pub fork: u64,     // 다른 가지와 갈라진 노드 (0 = 루트)
+    pub label: String, // 끝 노드의 커밋 설명
+    pub length: usize, // 루트부터 끝 노드까지 커밋 수
+    pub current: bool, // 현재 위치가 이 가지 위에 있는지
+}
+
+/// 부모 관계로부터 가지 목록 생성 (세션/테이블 트리 공용)
+pub fn collect_branches(parents: &HashMap<u64, u64>, head: u64, label: impl Fn(u64) -> String) -> Vec<BranchInfo> {
+    let mut child_count: HashMap<u64, usize> = HashMap::new();
+    for parent in parents.values() {
+        *child_count.entry(*parent).or_default() += 1;
+    }
+
+    let mut branches: Vec<BranchInfo> = parents
+        .keys()
+        .filter(|group| !child_count.contains_key(group))
+        .map(|&tip| {
+            let mut path = vec![tip];
+            let mut node = tip;
+            while let Some(&parent) = parents.get(&node) {
+                if parent == 0 {
+                    break;
+                }
+                path.push(parent);
+                node = parent;
+            }
+            let fork = path
+                .iter()
+                .skip(1)
+                .copied()
+                .find(|g| child_count.get(g).copied().unwrap_or(0) > 1)
+                .unwrap_or(0);
+            BranchInfo {
+                tip,
+                fork,
+                label: label(tip),
+                length: path.len(),
+                current: head == 0 || path.contains(&head),
+            }
+        })
+        .collect();
+    branches.sort_by_key(|b| b.tip);
+    branches
+}
+
+#[derive(Clone)]
+pub struct TxManager<K: TableKey = i32> {
+    undo_stack: Vec<TxDeltaList<K>>,
+    redo_stack: Vec<TxDeltaList<K>>,
+    current: TxDeltaList<K>,
+    tree_mode: bool,
+    nodes: HashMap<u64, UndoNode<K>>, // 트리 모드에서 커밋된 모든 노드
+    limit: HistoryLimit,
+    released: Vec<Arc<dyn DItem>>, // 버린 델타에만 있던 아이템 (take_released 로 꺼내 해제)
 }
 
-impl TxManager {
+impl<K: TableKey> Default for TxManager<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: TableKey> TxManager<K> {
     pub fn new() -> Self {
         TxManager {
             undo_stack: Vec::new(),
             redo_stack: Vec::new(),
             current: TxDeltaList::new(),
+            tree_mode: false,
+            nodes: HashMap::new(),
+            limit: HistoryLimit::default(),
+            released: Vec::new(),
         }
     }
 
+    /// undo 트리 모드: undo 후 새 커밋이 redo 경로를 지우지 않고 새 가지를 만듦
+    /// 기존 undo/redo 기록은 한 줄짜리 가지로 변환
+    pub fn set_tree_mode(&mut self, on: bool) {
+        self.tree_mode = on;
+        self.nodes.clear();
+        if !on {
+            return;
+        }
+        let chain: Vec<TxDeltaList<K>> = self.undo_stack.iter().chain(self.redo_stack.iter().rev()).cloned().collect();
+        let mut parent = 0;
+        for delta in chain {
+            let group = delta.group;
+            self.add_node(delta, parent);
+            parent = group;
+        }
+    }
+
+    fn add_node(&mut self, delta: TxDeltaList<K>, parent: u64) {
+        let group = delta.group;
+        if let Some(node) = self.nodes.get_mut(&parent) {
+            node.children.push(group);
+        }
+        self.nodes.insert(group, UndoNode {
+            delta,
+            parent,
+            children: Vec::new(),
+        });
+    }
+
+    pub fn is_tree_mode(&self) -> bool {
+        self.tree_mode
+    }
+
     /// 현재 트랜잭션에 액션 추가
     pub fn add(&mut self, action: TxAction) {
         self.current.add(action);
     }
 
-    /// 커밋: 현재 변경사항을 undo 스택에 저장
+    /// 커밋: 현재 변경사항을 새 그룹으로 undo 스택에 저장
     pub fn commit(&mut self) {
-        if self.current.count() > 0 {
-            self.undo_stack.push(std::mem::take(&mut self.current));
-            self.redo_stack.clear(); // 커밋 시 redo 초기화
+        self.commit_group(next_group_id(), "");
+    }
+
+    /// 설명을 붙여 커밋
+    pub fn commit_labelled(&mut self, label: &str) {
+        self.commit_group(next_group_id(), label);
+    }
+
+    /// 지정한 그룹 번호로 커밋 (세션 커밋은 여러 테이블이 같은 그룹을 공유)
+    pub fn commit_group(&mut self, group: u64, label: &str) -> bool {
+        if self.current.count() == 0 {
+            return false;
         }
+        let mut delta = std::mem::take(&mut self.current);
+        delta.group = group;
+        delta.label = label.to_string();
+        delta.timestamp = Some(SystemTime::now());
+        if self.tree_mode {
+            let parent = self.undo_group().unwrap_or(0);
+            self.add_node(delta.clone(), parent);
+        }
+        self.undo_stack.push(delta);
+        self.clear_redo(); // 커밋 시 redo 초기화
+        self.trim();
+        true
     }
 
     /// Undo: 마지막 변경사항을 되돌림
-    pub fn undo(&mut self) -> Option<TxDeltaList> {
+    pub fn undo(&mut self) -> Option<TxDeltaList<K>> {
         if let Some(delta) = self.undo_stack.pop() {
             self.redo_stack.push(delta.clone());
             Some(delta)
@@ -41,7 +207,7 @@ impl TxManager {
     }
 
     /// Redo: 마지막 undo를 다시 적용
-    pub fn redo(&mut self) -> Option<TxDeltaList> {
+    pub fn redo(&mut self) -> Option<TxDeltaList<K>> {
         if let Some(delta) = self.redo_stack.pop() {
             self.undo_stack.push(delta.clone());
             Some(delta)
@@ -50,11 +216,12 @@ impl TxManager {
         }
     }
 
-    /// 전체 초기화
+    /// 전체 초기화 (기록 한도는 유지)
     pub fn clear(&mut self) {
         self.undo_stack.clear();
         self.redo_stack.clear();
         self.current.clear();
+        self.nodes.clear();
     }
 
     /// 현재 트랜잭션 액션 수
@@ -62,6 +229,232 @@ impl TxManager {
         self.current.count()
     }
 
+    /// 아직 커밋되지 않은 현재 트랜잭션
+    pub fn current(&self) -> &TxDeltaList<K> {
+        &self.current
+    }
+
+    /// 현재 트랜잭션을 꺼내고 비움
+    pub fn take_current(&mut self) -> TxDeltaList<K> {
+        std::mem::take(&mut self.current)
+    }
+
+    /// 현재 트랜잭션 교체 (세이브포인트 복원용)
+    pub fn set_current(&mut self, delta: TxDeltaList<K>) {
+        self.current = delta;
+    }
+
+    /// 가장 최근에 적용된 커밋
+    pub fn last_committed(&self) -> Option<&TxDeltaList<K>> {
+        self.undo_stack.last()
+    }
+
+    /// 다음 undo 대상 그룹 번호
+    pub fn undo_group(&self) -> Option<u64> {
+        self.undo_stack.last().map(|d| d.group)
+    }
+
+    /// 다음 redo 대상 그룹 번호
+    pub fn redo_group(&self) -> Option<u64> {
+        self.redo_stack.last().map(|d| d.group)
+    }
+
+    /// 커밋 기록: 적용된 항목(오래된 순) 다음에 redo 대기 항목(다음 redo 순)
+    pub fn history(&self) -> Vec<HistoryEntry> {
+        let applied = self.undo_stack.iter().map(|d| (d, true));
+        let undone = self.redo_stack.iter().rev().map(|d| (d, false));
+        applied
+            .chain(undone)
+            .map(|(delta, applied)| HistoryEntry {
+                group: delta.group,
+                label: delta.label.clone(),
+                timestamp: delta.timestamp,
+                action_count: delta.count(),
+                applied,
+            })
+            .collect()
+    }
+
+    /// 현재 위치: 적용된 커밋 수 (history 의 앞에서부터 position 개가 적용됨)
+    pub fn position(&self) -> usize {
+        self.undo_stack.len()
+    }
+
+    /// 트리 노드 조회
+    pub fn node(&self, group: u64) -> Option<&UndoNode<K>> {
+        self.nodes.get(&group)
+    }
+
+    /// 루트에서 노드까지의 그룹 경로 (0 이면 빈 경로)
+    pub fn path_to(&self, group: u64) -> Option<Vec<u64>> {
+        let mut path = Vec::new();
+        let mut node = group;
+        while node != 0 {
+            path.push(node);
+            node = self.nodes.get(&node)?.parent;
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// 현재 적용된 그룹 (오래된 순)
+    pub fn applied_groups(&self) -> Vec<u64> {
+        self.undo_stack.iter().map(|d| d.group).collect()
+    }
+
+    /// 트리 노드들로 redo 경로 설정 (path 순서대로 redo 됨)
+    pub fn set_redo_path(&mut self, path: &[u64]) {
+        self.redo_stack = path
+            .iter()
+            .rev()
+            .filter_map(|g| self.nodes.get(g).map(|n| n.delta.clone()))
+            .collect();
+    }
+
+    /// 노드에서 가장 최근 자식을 따라간 경로
+    pub fn latest_path_from(&self, group: u64) -> Vec<u64> {
+        let mut path = Vec::new();
+        let mut node = group;
+        while let Some(child) = self.children_of(node).into_iter().max() {
+            path.push(child);
+            node = child;
+        }
+        path
+    }
+
+    /// 노드의 자식 목록 (0 = 루트)
+    pub fn children_of(&self, group: u64) -> Vec<u64> {
+        if group == 0 {
+            return self.nodes.iter().filter(|(_, n)| n.parent == 0).map(|(g, _)| *g).collect();
+        }
+        self.nodes.get(&group).map(|n| n.children.clone()).unwrap_or_default()
+    }
+
+    /// 트리의 가지 목록
+    pub fn branches(&self) -> Vec<BranchInfo> {
+        let parents: HashMap<u64, u64> = self.nodes.iter().map(|(g, n)| (*g, n.parent)).collect();
+        let head = self.undo_group().unwrap_or(0);
+        collect_branches(&parents, head, |g| self.nodes[&g].delta.label.clone())
+    }
+
+    /// redo 경로 비우기 (트리 모드에서는 노드가 델타를 계속 보관)
+    pub fn clear_redo(&mut self) {
+        let discarded = std::mem::take(&mut self.redo_stack);
+        self.release(discarded);
+    }
+
+    /// 버린 델타의 아이템 중 남은 기록 (undo, redo, 트리 노드, 현재 트랜잭션) 어디에도 없는 것을 released 에 모음
+    fn release(&mut self, discarded: Vec<TxDeltaList<K>>) {
+        if discarded.is_empty() {
+            return;
+        }
+        let held: HashSet<usize> = self
+            .undo_stack
+            .iter()
+            .chain(&self.redo_stack)
+            .chain(self.nodes.values().map(|n| &n.delta))
+            .chain(std::iter::once(&self.current))
+            .flat_map(|d| d.cursors().map(|c| item_id(&c.data)).collect::<Vec<_>>())
+            .collect();
+        let mut seen: HashSet<usize> = self.released.iter().map(item_id).collect();
+        for delta in &discarded {
+            for cursor in delta.cursors() {
+                let id = item_id(&cursor.data);
+                if !held.contains(&id) && seen.insert(id) {
+                    self.released.push(cursor.data.clone());
+                }
+            }
+        }
+    }
+
+    /// 더 이상 기록에서 복원할 수 없는 아이템을 꺼냄 (테이블에 살아 있는 것은 호출한 쪽에서 걸러냄)
+    pub fn take_released(&mut self) -> Vec<Arc<dyn DItem>> {
+        std::mem::take(&mut self.released)
+    }
+
+    pub fn has_released(&self) -> bool {
+        !self.released.is_empty()
+    }
+
+    /// 기록 한도 설정 (초과분은 바로 정리)
+    pub fn set_history_limit(&mut self, limit: HistoryLimit) {
+        self.limit = limit;
+        self.trim();
+    }
+
+    pub fn history_limit(&self) -> HistoryLimit {
+        self.limit
+    }
+
+    /// 보관 중인 커밋 수
+    pub fn step_count(&self) -> usize {
+        if self.tree_mode {
+            self.nodes.len()
+        } else {
+            self.undo_stack.len() + self.redo_stack.len()
+        }
+    }
+
+    /// 보관 중인 기록의 대략적인 메모리 사용량 (바이트)
+    pub fn memory_usage(&self) -> usize {
+        let stored: usize = if self.tree_mode {
+            self.nodes.values().map(|n| n.delta.approx_bytes()).sum()
+        } else {
+            self.undo_stack.iter().chain(&self.redo_stack).map(|d| d.approx_bytes()).sum()
+        };
+        stored + self.current.approx_bytes()
+    }
+
+    /// 한도를 넘으면 가장 오래된 커밋부터 버림 (버린 커밋 수 반환)
+    pub fn trim(&mut self) -> usize {
+        let mut removed = 0;
+        while self.limit.is_exceeded(self.step_count(), self.memory_usage()) {
+            let Some(oldest) = self.undo_stack.first().map(|d| d.group) else {
+                break;
+            };
+            let groups = self.expired_groups(oldest);
+            removed += groups.len();
+            self.discard_groups(&groups);
+        }
+        removed
+    }
+
+    /// 가장 오래된 커밋을 버릴 때 함께 사라지는 그룹
+    /// (트리 모드에서는 루트에서 갈라진 다른 가지도 더 이상 도달할 수 없음)
+    pub fn expired_groups(&self, oldest: u64) -> HashSet<u64> {
+        let mut groups = HashSet::from([oldest]);
+        if self.tree_mode {
+            let mut stack: Vec<u64> = self.children_of(0).into_iter().filter(|g| *g != oldest).collect();
+            while let Some(group) = stack.pop() {
+                groups.insert(group);
+                stack.extend(self.children_of(group));
+            }
+        }
+        groups
+    }
+
+    /// 지정한 그룹들을 기록에서 제거 (남은 자식은 루트에 연결)
+    pub fn discard_groups(&mut self, groups: &HashSet<u64>) {
+        let mut discarded = Vec::new();
+        for stack in [&mut self.undo_stack, &mut self.redo_stack] {
+            let (drop, keep): (Vec<_>, Vec<_>) = std::mem::take(stack).into_iter().partition(|d| groups.contains(&d.group));
+            *stack = keep;
+            discarded.extend(drop);
+        }
+        for group in groups {
+            if let Some(node) = self.nodes.remove(group) {
+                discarded.push(node.delta);
+            }
+        }
+        for node in self.nodes.values_mut() {
+            if groups.contains(&node.parent) {
+                node.parent = 0;
+            }
+            node.children.retain(|g| !groups.contains(g));
+        }
+        self.release(discarded);
+    }
+
     pub fn has_undo(&self) -> bool {
         !self.undo_stack.is_empty()
     }
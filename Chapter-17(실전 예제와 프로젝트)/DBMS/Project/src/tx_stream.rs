@@ -1,79 +1,458 @@
 use crate::item::Cursor;
 use crate::guid::Guid;
-use std::fs::File;
-use std::hash::Hasher;
-use std::io::{Read, Write, BufReader, BufWriter};
-use std::sync::{Arc, Mutex};
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Seek, Write, BufReader, BufWriter};
+use std::sync::Mutex;
 use crate::define::TxAction;
-use crate::item_factory::{item_factory, ItemFactory};
+use crate::item_factory::ItemFactory;
+use crate::session::Session;
+
+/// 액션 레코드 형식 (1: 페이로드 없음, 2: 페이로드 포함, 3: 페이로드에 스키마 버전 포함)
+pub const ACTION_FORMAT: u32 = 3;
 
 pub trait TxStream {
-    fn write_guid(&mut self, guid: &Guid);
-    fn read_guid(&mut self) -> Option<Guid>;
+    fn write_u8(&mut self, value: u8);
+    fn read_u8(&mut self) -> Option<u8>;
+
+    fn write_u16(&mut self, value: u16);
+    fn read_u16(&mut self) -> Option<u16>;
 
     fn write_u32(&mut self, value: u32);
     fn read_u32(&mut self) -> Option<u32>;
 
+    fn write_bytes(&mut self, data: &[u8]);
+    fn read_bytes(&mut self, len: usize) -> Option<Vec<u8>>;
+
     fn flush(&mut self);
-    fn write_action(&mut self, action: &TxAction);
-    fn read_action(&mut self, item_type: u16, factory: &Mutex<ItemFactory>) -> Option<TxAction>;
+
+    fn write_guid(&mut self, guid: &Guid) {
+        self.write_u32(guid.data1);
+        self.write_u16(guid.data2);
+        self.write_u16(guid.data3);
+        self.write_bytes(&guid.data4);
+    }
+
+    fn read_guid(&mut self) -> Option<Guid> {
+        let data1 = self.read_u32()?;
+        let data2 = self.read_u16()?;
+        let data3 = self.read_u16()?;
+        let data4 = self.read_bytes(8)?.try_into().ok()?;
+        Some(Guid { data1, data2, data3, data4 })
+    }
+
+    /// 아이템 페이로드: 스키마 버전(u16) + 길이(u32) + DItem::serialize 결과
+    fn write_payload(&mut self, cursor: &Cursor, session: &Session) {
+        let mut payload = MemTxStream::new();
+        cursor.data.serialize(&mut payload, session);
+        self.write_u16(cursor.data.schema_version());
+        self.write_u32(payload.as_bytes().len() as u32);
+        self.write_bytes(payload.as_bytes());
+    }
+
+    /// (스키마 버전, 페이로드)
+    fn read_payload(&mut self) -> Option<(u16, Vec<u8>)> {
+        let version = self.read_u16()?;
+        let len = self.read_u32()? as usize;
+        Some((version, self.read_bytes(len)?))
+    }
+
+    fn write_action(&mut self, action: &TxAction, session: &Session) {
+        match action {
+            TxAction::Insert(cursor) => {
+                self.write_u32(cursor.key() as u32);
+                self.write_u8(0x01); // 상태: Insert
+                self.write_u8(cursor.param_data);
+                self.write_u32(cursor.param as u32);
+                self.write_payload(cursor, session);
+            }
+            TxAction::Remove(cursor) => {
+                self.write_u32(cursor.key() as u32);
+                self.write_u8(0x02); // 상태: Remove
+                self.write_u8(cursor.param_data);
+                self.write_u32(cursor.param as u32);
+                self.write_payload(cursor, session);
+            }
+            TxAction::Modify { before, after } => {
+                self.write_u32(after.key() as u32);
+                self.write_u8(0x03); // 상태: Modify
+                self.write_u8(after.param_data);
+                self.write_u32(after.param as u32);
+                self.write_payload(after, session);
+                self.write_u8(before.param_data); // 이전 이미지
+                self.write_u32(before.param as u32);
+                self.write_payload(before, session);
+            }
+            TxAction::Cancelled => {
+                // 키 자리는 0, 페이로드 없음 (읽을 때 상태만 보고 끝냄)
+                self.write_u32(0);
+                self.write_u8(0xFF); // 상태: Cancelled
+            }
+        }
+    }
+
+    fn read_action(&mut self, item_type: u16, factory: &Mutex<ItemFactory>) -> Option<TxAction> {
+        self.try_read_action(item_type, factory).ok().flatten()
+    }
+
+    /// 액션 읽기: Ok(None) = 스트림이 중간에 끊김, Err = 페이로드 변환/복원 실패
+    fn try_read_action(&mut self, item_type: u16, factory: &Mutex<ItemFactory>) -> io::Result<Option<TxAction>> {
+        self.try_read_action_format(item_type, factory, ACTION_FORMAT)
+    }
+
+    /// 이전 형식으로 기록된 액션 읽기
+    /// (format 1: 페이로드 없음 → create 로 아이템 생성, 2: 스키마 버전 없음 → 버전 1)
+    fn try_read_action_format(
+        &mut self,
+        item_type: u16,
+        factory: &Mutex<ItemFactory>,
+        format: u32,
+    ) -> io::Result<Option<TxAction>> {
+        let mut read = || {
+            let key = self.read_u32()? as i32;
+            let status = self.read_u8()?;
+            if status == 0xFF {
+                return Some(None); // 취소된 항목: 페이로드 없음
+            }
+            let mut image = || {
+                let param_data = self.read_u8()?;
+                let param = self.read_u32()? as usize;
+                let payload = match format {
+                    1 => None,
+                    2 => {
+                        let len = self.read_u32()? as usize;
+                        Some((1, self.read_bytes(len)?))
+                    }
+                    _ => Some(self.read_payload()?),
+                };
+                Some((param_data, param, payload))
+            };
+            let after = image()?;
+            let before = if status == 0x03 { Some(image()?) } else { None };
+            Some(Some((key, status, after, before)))
+        };
+        let (key, status, after, before) = match read() {
+            Some(Some(record)) => record,
+            Some(None) => return Ok(Some(TxAction::Cancelled)),
+            None => return Ok(None),
+        };
+
+        let factory = factory
+            .lock()
+            .map_err(|_| io::Error::other("item factory lock poisoned"))?;
+        let restore = |(param_data, param, payload): (u8, usize, Option<(u16, Vec<u8>)>)| {
+            let item = match payload {
+                Some((version, payload)) => factory.deserialize_item(item_type, key, version, payload)?,
+                None => factory.create_item(item_type, key).ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("item_type {} key {} cannot be created", item_type, key),
+                    )
+                })?,
+            };
+            let mut cursor = Cursor::new(item);
+            cursor.param_data = param_data;
+            cursor.param = param;
+            Ok::<Cursor, io::Error>(cursor)
+        };
+
+        let action = match (status, before) {
+            (0x01, _) => TxAction::Insert(restore(after)?), // 삭제된 항목 → 복원
+            (0x02, _) => TxAction::Remove(restore(after)?), // 삽입된 항목 → 삭제
+            (0x03, Some(before)) => {
+                // 수정된 항목: 이전 이미지를 따로 복원
+                TxAction::Modify {
+                    before: restore(before)?,
+                    after: restore(after)?,
+                }
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some(action))
+    }
+}
+
+
+
+/// 스트림에 읽고 쓸 수 있는 필드 값 (#[derive(DItem)] 의 필드 직렬화에 사용, 리틀 엔디안)
+pub trait StreamValue: Sized {
+    fn write_to(&self, stream: &mut dyn TxStream);
+    fn read_from(stream: &mut dyn TxStream) -> Option<Self>;
+}
+
+impl StreamValue for u8 {
+    fn write_to(&self, stream: &mut dyn TxStream) {
+        stream.write_u8(*self);
+    }
+
+    fn read_from(stream: &mut dyn TxStream) -> Option<Self> {
+        stream.read_u8()
+    }
+}
+
+impl StreamValue for u16 {
+    fn write_to(&self, stream: &mut dyn TxStream) {
+        stream.write_u16(*self);
+    }
+
+    fn read_from(stream: &mut dyn TxStream) -> Option<Self> {
+        stream.read_u16()
+    }
 }
 
+impl StreamValue for u32 {
+    fn write_to(&self, stream: &mut dyn TxStream) {
+        stream.write_u32(*self);
+    }
+
+    fn read_from(stream: &mut dyn TxStream) -> Option<Self> {
+        stream.read_u32()
+    }
+}
 
+impl StreamValue for i32 {
+    fn write_to(&self, stream: &mut dyn TxStream) {
+        stream.write_u32(*self as u32);
+    }
+
+    fn read_from(stream: &mut dyn TxStream) -> Option<Self> {
+        Some(stream.read_u32()? as i32)
+    }
+}
+
+impl StreamValue for u64 {
+    fn write_to(&self, stream: &mut dyn TxStream) {
+        stream.write_bytes(&self.to_le_bytes());
+    }
+
+    fn read_from(stream: &mut dyn TxStream) -> Option<Self> {
+        Some(u64::from_le_bytes(stream.read_bytes(8)?.try_into().ok()?))
+    }
+}
+
+impl StreamValue for i64 {
+    fn write_to(&self, stream: &mut dyn TxStream) {
+        (*self as u64).write_to(stream);
+    }
+
+    fn read_from(stream: &mut dyn TxStream) -> Option<Self> {
+        Some(u64::read_from(stream)? as i64)
+    }
+}
+
+impl StreamValue for f32 {
+    fn write_to(&self, stream: &mut dyn TxStream) {
+        stream.write_u32(self.to_bits());
+    }
 
+    fn read_from(stream: &mut dyn TxStream) -> Option<Self> {
+        Some(f32::from_bits(stream.read_u32()?))
+    }
+}
+
+impl StreamValue for f64 {
+    fn write_to(&self, stream: &mut dyn TxStream) {
+        self.to_bits().write_to(stream);
+    }
+
+    fn read_from(stream: &mut dyn TxStream) -> Option<Self> {
+        Some(f64::from_bits(u64::read_from(stream)?))
+    }
+}
+
+impl StreamValue for bool {
+    fn write_to(&self, stream: &mut dyn TxStream) {
+        stream.write_u8(*self as u8);
+    }
+
+    fn read_from(stream: &mut dyn TxStream) -> Option<Self> {
+        match stream.read_u8()? {
+            0 => Some(false),
+            1 => Some(true),
+            _ => None,
+        }
+    }
+}
+
+impl StreamValue for Guid {
+    fn write_to(&self, stream: &mut dyn TxStream) {
+        stream.write_guid(self);
+    }
+
+    fn read_from(stream: &mut dyn TxStream) -> Option<Self> {
+        stream.read_guid()
+    }
+}
+
+/// 길이(u32) + UTF-8 바이트
+impl StreamValue for String {
+    fn write_to(&self, stream: &mut dyn TxStream) {
+        stream.write_u32(self.len() as u32);
+        stream.write_bytes(self.as_bytes());
+    }
+
+    fn read_from(stream: &mut dyn TxStream) -> Option<Self> {
+        let len = stream.read_u32()? as usize;
+        String::from_utf8(stream.read_bytes(len)?).ok()
+    }
+}
+
+/// 개수(u32) + 각 요소
+impl<T: StreamValue> StreamValue for Vec<T> {
+    fn write_to(&self, stream: &mut dyn TxStream) {
+        stream.write_u32(self.len() as u32);
+        for value in self {
+            value.write_to(stream);
+        }
+    }
+
+    fn read_from(stream: &mut dyn TxStream) -> Option<Self> {
+        let len = stream.read_u32()? as usize;
+        (0..len).map(|_| T::read_from(stream)).collect()
+    }
+}
+
+/// 있음 표시(u8) + 값
+impl<T: StreamValue> StreamValue for Option<T> {
+    fn write_to(&self, stream: &mut dyn TxStream) {
+        match self {
+            Some(value) => {
+                stream.write_u8(1);
+                value.write_to(stream);
+            }
+            None => stream.write_u8(0),
+        }
+    }
+
+    fn read_from(stream: &mut dyn TxStream) -> Option<Self> {
+        match stream.read_u8()? {
+            0 => Some(None),
+            1 => Some(Some(T::read_from(stream)?)),
+            _ => None,
+        }
+    }
+}
+
+/// 요소를 순서대로 기록 (복합 키)
+impl<A: StreamValue, B: StreamValue> StreamValue for (A, B) {
+    fn write_to(&self, stream: &mut dyn TxStream) {
+        self.0.write_to(stream);
+        self.1.write_to(stream);
+    }
+
+    fn read_from(stream: &mut dyn TxStream) -> Option<Self> {
+        Some((A::read_from(stream)?, B::read_from(stream)?))
+    }
+}
+
+impl<A: StreamValue, B: StreamValue, C: StreamValue> StreamValue for (A, B, C) {
+    fn write_to(&self, stream: &mut dyn TxStream) {
+        self.0.write_to(stream);
+        self.1.write_to(stream);
+        self.2.write_to(stream);
+    }
+
+    fn read_from(stream: &mut dyn TxStream) -> Option<Self> {
+        Some((A::read_from(stream)?, B::read_from(stream)?, C::read_from(stream)?))
+    }
+}
+
+
+/// 파일 스트림: 쓰기 오류는 패닉 대신 기록해 두었다가 sync 에서 반환
 pub struct FileTxStream {
-    writer: BufWriter<File>,
+    writer: Option<BufWriter<File>>,
     reader: Option<BufReader<File>>,
+    error: Option<io::Error>, // 첫 쓰기 오류 (이후 쓰기는 무시)
 }
 
 impl FileTxStream {
     pub fn new_write(path: &str) -> std::io::Result<Self> {
         let file = File::create(path)?;
         Ok(FileTxStream {
-            writer: BufWriter::new(file),
+            writer: Some(BufWriter::new(file)),
             reader: None,
+            error: None,
         })
     }
 
     pub fn new_read(path: &str) -> std::io::Result<Self> {
         let file = File::open(path)?;
         Ok(FileTxStream {
-            writer: BufWriter::new(File::create("/dev/null")?), // dummy writer
+            writer: None,
             reader: Some(BufReader::new(file)),
+            error: None,
         })
     }
 
-    pub fn write_u16(&mut self, value: u16) {
-        self.writer.write_all(&value.to_le_bytes()).unwrap();
+    /// 기존 파일 끝에 이어 쓰기 (없으면 생성)
+    pub fn new_append(path: &str) -> std::io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(FileTxStream {
+            writer: Some(BufWriter::new(file)),
+            reader: None,
+            error: None,
+        })
     }
 
-    pub fn read_u16(&mut self) -> Option<u16> {
-        let mut buf = [0u8; 2];
-        self.reader.as_mut()?.read_exact(&mut buf).ok()?;
-        Some(u16::from_le_bytes(buf))
+    /// 현재 읽기 위치 (바이트)
+    pub fn position(&mut self) -> Option<u64> {
+        self.reader.as_mut()?.stream_position().ok()
+    }
+
+    /// 버퍼를 비우고 디스크까지 동기화 (앞서 실패한 쓰기가 있으면 그 오류 반환)
+    pub fn sync(&mut self) -> std::io::Result<()> {
+        if let Some(error) = self.error.take() {
+            return Err(error);
+        }
+        let writer = self.writer.as_mut().ok_or_else(not_writable)?;
+        writer.flush()?;
+        writer.get_ref().sync_data()
+    }
+
+    /// 아직 파일에 쓰지 않은 버퍼를 버리고 닫음 (실패한 레코드가 파일에 남지 않도록)
+    pub fn discard(self) {
+        if let Some(writer) = self.writer {
+            let _ = writer.into_parts();
+        }
+    }
+
+    fn write_raw(&mut self, data: &[u8]) {
+        if self.error.is_some() {
+            return;
+        }
+        let result = match self.writer.as_mut() {
+            Some(writer) => writer.write_all(data),
+            None => Err(not_writable()),
+        };
+        if let Err(error) = result {
+            self.error = Some(error);
+        }
     }
 }
 
 
 impl TxStream for FileTxStream {
-    fn write_guid(&mut self, guid: &Guid) {
-        self.write_u32(guid.data1);
-        self.write_u16(guid.data2);
-        self.write_u16(guid.data3);
-        self.writer.write_all(&guid.data4).unwrap();
+    fn write_u8(&mut self, value: u8) {
+        self.write_raw(&[value]);
     }
 
-    fn read_guid(&mut self) -> Option<Guid> {
-        let data1 = self.read_u32()?;
-        let data2 = self.read_u16()?;
-        let data3 = self.read_u16()?;
-        let mut data4 = [0u8; 8];
-        self.reader.as_mut()?.read_exact(&mut data4).ok()?;
-        Some(Guid { data1, data2, data3, data4 })
+    fn read_u8(&mut self) -> Option<u8> {
+        let mut buf = [0u8; 1];
+        self.reader.as_mut()?.read_exact(&mut buf).ok()?;
+        Some(buf[0])
+    }
+
+    fn write_u16(&mut self, value: u16) {
+        self.write_raw(&value.to_le_bytes());
+    }
+
+    fn read_u16(&mut self) -> Option<u16> {
+        let mut buf = [0u8; 2];
+        self.reader.as_mut()?.read_exact(&mut buf).ok()?;
+        Some(u16::from_le_bytes(buf))
     }
 
     fn write_u32(&mut self, value: u32) {
-        self.writer.write_all(&value.to_le_bytes()).unwrap();
+        self.write_raw(&value.to_le_bytes());
     }
 
     fn read_u32(&mut self) -> Option<u32> {
@@ -82,73 +461,105 @@ impl TxStream for FileTxStream {
         Some(u32::from_le_bytes(buf))
     }
 
+    fn write_bytes(&mut self, data: &[u8]) {
+        self.write_raw(data);
+    }
 
+    fn read_bytes(&mut self, len: usize) -> Option<Vec<u8>> {
+        let mut buf = Vec::new();
+        self.reader.as_mut()?.take(len as u64).read_to_end(&mut buf).ok()?;
+        if buf.len() != len {
+            return None;
+        }
+        Some(buf)
+    }
 
-
-    fn write_action(&mut self, action: &TxAction) {
-        match action {
-            TxAction::Insert(cursor) => {
-                self.write_u32(cursor.key() as u32);
-                self.writer.write_all(&[0x01]).unwrap(); // 상태: Insert
-                self.writer.write_all(&[cursor.param_data]).unwrap();
-                self.write_u32(cursor.param as u32);
-            }
-            TxAction::Remove(cursor) => {
-                self.write_u32(cursor.key() as u32);
-                self.writer.write_all(&[0x02]).unwrap(); // 상태: Remove
-                self.writer.write_all(&[cursor.param_data]).unwrap();
-                self.write_u32(cursor.param as u32);
-            }
-            TxAction::Modify { after, .. } => {
-                self.write_u32(after.key() as u32);
-                self.writer.write_all(&[0x03]).unwrap(); // 상태: Modify
-                self.writer.write_all(&[after.param_data]).unwrap();
-                self.write_u32(after.param as u32);
-            }
-            TxAction::Cancelled => {
-                // 생략하거나 특별한 마커로 기록
-                self.writer.write_all(&[0xFF]).unwrap(); // 상태: Cancelled
-            }
+    fn flush(&mut self) {
+        if self.error.is_some() {
+            return;
+        }
+        if let Some(Err(error)) = self.writer.as_mut().map(|w| w.flush()) {
+            self.error = Some(error);
         }
     }
+}
 
+fn not_writable() -> io::Error {
+    io::Error::new(io::ErrorKind::Unsupported, "stream is not open for writing")
+}
 
-    fn read_action(&mut self, item_type: u16, factory: &Mutex<ItemFactory>) -> Option<TxAction> {
-        let key = self.read_u32()? as i32;
 
-        let mut status = [0u8; 1];
-        let mut param_data = [0u8; 1];
+/// 메모리 버퍼 스트림: 아이템 페이로드를 길이와 함께 기록할 때 사용
+#[derive(Default)]
+pub struct MemTxStream {
+    buffer: Vec<u8>,
+    pos: usize,
+}
 
-        self.reader.as_mut()?.read_exact(&mut status).ok()?;
-        self.reader.as_mut()?.read_exact(&mut param_data).ok()?;
+impl MemTxStream {
+    pub fn new() -> Self {
+        MemTxStream::default()
+    }
 
-        let param = self.read_u32()? as usize;
+    pub fn from_bytes(buffer: Vec<u8>) -> Self {
+        MemTxStream { buffer, pos: 0 }
+    }
 
-        let factory = factory.lock().ok()?;
-        let item = factory.create_item(item_type, key)?;
-        let mut cursor = Cursor::new(item);
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.buffer
+    }
 
-        cursor.param_data = param_data[0];
-        cursor.param = param;
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.buffer
+    }
 
-        match status[0] {
-            0x01 => Some(TxAction::Insert(cursor)), // 삭제된 항목 → 복원
-            0x02 => Some(TxAction::Remove(cursor)), // 삽입된 항목 → 삭제
-            0x03 => {
-                // 수정된 항목 → 수정 복원
-                // 이 경우 before/after를 따로 읽어야 함 (추가 구조 필요)
-                None // 또는 수정 로직 구현
-            }
-            0xFF => Some(TxAction::Cancelled), // 취소된 항목
-            _ => None,
+    /// 아직 읽지 않은 바이트 수
+    pub fn remaining(&self) -> usize {
+        self.buffer.len() - self.pos
+    }
+
+    fn take(&mut self, len: usize) -> Option<&[u8]> {
+        if self.remaining() < len {
+            return None;
         }
+        let slice = &self.buffer[self.pos..self.pos + len];
+        self.pos += len;
+        Some(slice)
+    }
+}
+
+impl TxStream for MemTxStream {
+    fn write_u8(&mut self, value: u8) {
+        self.buffer.push(value);
     }
 
+    fn read_u8(&mut self) -> Option<u8> {
+        Some(self.take(1)?[0])
+    }
 
+    fn write_u16(&mut self, value: u16) {
+        self.buffer.extend_from_slice(&value.to_le_bytes());
+    }
 
+    fn read_u16(&mut self) -> Option<u16> {
+        Some(u16::from_le_bytes(self.take(2)?.try_into().ok()?))
+    }
 
+    fn write_u32(&mut self, value: u32) {
+        self.buffer.extend_from_slice(&value.to_le_bytes());
+    }
 
-    fn flush(&mut self) {
-        self.writer.flush().unwrap();
+    fn read_u32(&mut self) -> Option<u32> {
+        Some(u32::from_le_bytes(self.take(4)?.try_into().ok()?))
+    }
+
+    fn write_bytes(&mut self, data: &[u8]) {
+        self.buffer.extend_from_slice(data);
     }
+
+    fn read_bytes(&mut self, len: usize) -> Option<Vec<u8>> {
+        Some(self.take(len)?.to_vec())
+    }
+
+    fn flush(&mut self) {}
 }
@@ -1,9 +1,14 @@
 #[cfg(test)]
 mod tests {
-    use std::sync::Arc;
-    use crate::item::{DItem, Cursor};
+    use std::sync::{Arc, Mutex};
+    use crate::define::TxAction;
+    use crate::item::{Cursor, DItem};
     use crate::session::Session;
-    use crate::item_factory::{item_factory, item_factory_mut};
+    use crate::item_factory::item_factory_mut;
+    use crate::observer::{ChangeBatch, ChangeKind, ChangeSource};
+    use crate::tx_delta_list::TxDeltaList;
+    use crate::tx_manager::HistoryLimit;
+    use crate::tx_stream::{MemTxStream, TxStream};
 
     #[derive(Debug)]
     struct MyItem {
@@ -47,6 +52,8 @@ mod tests {
         let cursor = table.insert(42, factory).unwrap();
         assert_eq!(cursor.key(), 42);
         assert!(table.get(42).is_some());
+        // 같은 트랜잭션의 삽입과 삭제는 서로 상쇄되므로 삽입을 먼저 커밋 (아래 test_insert_and_remove_in_one_commit_cancel)
+        table.tx.commit();
 
         println!("test1");
 
@@ -62,7 +69,7 @@ mod tests {
 
         {
             // Undo
-            session.undo_all();
+            session.undo_all().unwrap();
             assert!(session.get_table(10).unwrap().get(42).is_some());
             println!("test3");
 
@@ -70,7 +77,7 @@ mod tests {
 
         {
             // Redo
-            session.redo_all();
+            session.redo_all().unwrap();
             assert!(session.get_table(10).unwrap().get(42).is_none());
 
             println!("test4");
@@ -78,4 +85,405 @@ mod tests {
 
 
     }
+
+    fn register_my_item() {
+        register_test_type(100, 10);
+    }
+
+    /// MyItem 을 지정한 타입으로 등록 (이미 등록된 타입이면 무시됨)
+    fn register_test_type(item_type: u16, table_type: u16) {
+        let mut factory = item_factory_mut().lock().unwrap();
+        factory.register_type(
+            item_type,
+            table_type,
+            Arc::new(move |key| Arc::new(MyItem {
+                key,
+                item_type,
+                table_type,
+            })),
+            Arc::new(|_item| {}),
+        );
+        // 페이로드가 비어 있으므로 키만으로 복원
+        factory.register_deserializer(
+            item_type,
+            Arc::new(move |key, _stream| Some(Arc::new(MyItem { key, item_type, table_type }))),
+        );
+    }
+
+    #[test]
+    fn test_modify_undo_redo() {
+        register_my_item();
+        let factory = item_factory_mut();
+
+        let mut session = Session::new();
+        session.register_table(10, 100);
+        let table = session.get_table_mut(10).unwrap();
+        let original = table.insert(7, factory).unwrap();
+        table.tx.commit();
+
+        // 수정: 같은 키의 커서는 하나만 남음
+        let new_item: Arc<dyn DItem> = Arc::new(MyItem { key: 7, item_type: 100, table_type: 10 });
+        let modified = table.modify(7, new_item.clone()).unwrap();
+        assert_eq!(table.items.find(7).unwrap().len(), 1);
+        assert!(Arc::ptr_eq(&table.get(7).unwrap().data, &new_item));
+        table.tx.commit();
+
+        // 다른 키로 수정 불가
+        assert!(table.modify(8, new_item.clone()).is_none());
+
+        session.undo_all().unwrap();
+        let table = session.get_table(10).unwrap();
+        assert_eq!(table.items.find(7).unwrap().len(), 1);
+        assert!(Arc::ptr_eq(&table.get(7).unwrap().data, &original.data));
+
+        session.redo_all().unwrap();
+        let table = session.get_table(10).unwrap();
+        assert!(Arc::ptr_eq(&table.get(7).unwrap().data, &modified.data));
+    }
+
+    #[test]
+    fn test_modify_stream_round_trip() {
+        register_my_item();
+        let factory = item_factory_mut();
+
+        let mut before = crate::item::Cursor::new(Arc::new(MyItem { key: 5, item_type: 100, table_type: 10 }));
+        before.set_param_data(1);
+        before.set_param(10);
+        let mut after = before.clone();
+        after.set_param_data(2);
+        after.set_param(20);
+
+        let mut stream = MemTxStream::new();
+        stream.write_action(&TxAction::Modify { before, after }, &Session::new());
+
+        let mut stream = MemTxStream::from_bytes(stream.into_bytes());
+        match stream.read_action(100, factory) {
+            Some(TxAction::Modify { before, after }) => {
+                assert_eq!((before.key(), before.param_data, before.param), (5, 1, 10));
+                assert_eq!((after.key(), after.param_data, after.param), (5, 2, 20));
+            }
+            other => panic!("unexpected action: {:?}", other),
+        }
+        assert_eq!(stream.remaining(), 0);
+    }
+
+    #[test]
+    fn test_session_undo_groups_span_tables() {
+        register_my_item();
+        register_test_type(101, 11);
+        let factory = item_factory_mut();
+
+        let mut session = Session::new();
+        session.register_table(10, 100);
+        session.register_table(11, 101);
+
+        // 두 테이블을 수정하는 하나의 커밋
+        session.get_table_mut(10).unwrap().insert(1, factory);
+        session.get_table_mut(11).unwrap().insert(1, factory);
+        session.commit_all().unwrap();
+
+        // 테이블 10만 수정하는 커밋
+        session.get_table_mut(10).unwrap().insert(2, factory);
+        session.commit_all().unwrap();
+
+        session.undo_all().unwrap();
+        assert!(session.get_table(10).unwrap().get(2).is_none());
+        assert!(session.get_table(10).unwrap().get(1).is_some());
+        assert!(session.get_table(11).unwrap().get(1).is_some()); // 건드리지 않은 테이블은 그대로
+
+        session.undo_all().unwrap();
+        assert!(session.get_table(10).unwrap().get(1).is_none());
+        assert!(session.get_table(11).unwrap().get(1).is_none());
+        assert!(!session.has_undo());
+
+        session.redo_all().unwrap();
+        assert!(session.get_table(10).unwrap().get(1).is_some());
+        assert!(session.get_table(11).unwrap().get(1).is_some());
+        assert!(session.get_table(10).unwrap().get(2).is_none());
+
+        // 새 커밋은 세션 전체의 redo 를 무효화
+        session.get_table_mut(11).unwrap().insert(3, factory);
+        session.commit_all().unwrap();
+        assert!(!session.has_redo());
+    }
+
+    #[test]
+    fn test_labelled_history_undo_to_redo_to() {
+        register_my_item();
+        let factory = item_factory_mut();
+
+        let mut session = Session::new();
+        session.register_table(10, 100);
+        for (key, label) in [(1, "Add first"), (2, "Add second"), (3, "Add third")] {
+            let table = session.get_table_mut(10).unwrap();
+            table.insert(key, factory);
+            if key == 3 {
+                table.insert(4, factory);
+            }
+            session.commit_labelled(label).unwrap();
+        }
+
+        let history = session.history();
+        let labels: Vec<&str> = history.iter().map(|e| e.label.as_str()).collect();
+        assert_eq!(labels, ["Add first", "Add second", "Add third"]);
+        assert_eq!(history[2].action_count, 2);
+        assert!(history.iter().all(|e| e.applied && e.timestamp.is_some()));
+
+        // 첫 번째 커밋 직후 상태로 이동
+        assert_eq!(session.undo_to(1).unwrap(), 2);
+        assert_eq!(session.history_position(), 1);
+        let table = session.get_table(10).unwrap();
+        assert!(table.get(1).is_some());
+        assert!(table.get(2).is_none());
+        assert!(table.get(4).is_none());
+        assert!(!session.history()[1].applied);
+
+        assert_eq!(session.redo_to(3).unwrap(), 2);
+        assert!(session.get_table(10).unwrap().get(4).is_some());
+        assert_eq!(session.history_position(), 3);
+    }
+
+    #[test]
+    fn test_undo_tree_keeps_alternate_branches() {
+        register_my_item();
+        let factory = item_factory_mut();
+
+        let mut session = Session::new();
+        session.register_table(10, 100);
+        session.enable_undo_tree();
+
+        session.get_table_mut(10).unwrap().insert(1, factory);
+        session.commit_labelled("A").unwrap();
+        session.get_table_mut(10).unwrap().insert(2, factory);
+        session.commit_labelled("B").unwrap();
+        let b = session.undo_tree_head().unwrap();
+
+        // undo 후 새 커밋 → 새 가지
+        session.undo_all().unwrap();
+        session.get_table_mut(10).unwrap().insert(3, factory);
+        session.commit_labelled("C").unwrap();
+        let c = session.undo_tree_head().unwrap();
+
+        let branches = session.branches();
+        let tips: Vec<(&str, bool)> = branches.iter().map(|b| (b.label.as_str(), b.current)).collect();
+        assert_eq!(tips, [("B", false), ("C", true)]);
+        assert_eq!(branches[0].fork, branches[1].fork);
+
+        // 이전 가지로 전환
+        assert!(session.goto(b).unwrap());
+        let table = session.get_table(10).unwrap();
+        assert!(table.get(2).is_some());
+        assert!(table.get(3).is_none());
+
+        // 루트까지 undo 후 redo 는 마지막으로 방문한 가지를 따라감
+        session.undo_to(0).unwrap();
+        assert!(session.get_table(10).unwrap().get(1).is_none());
+        session.redo_to(2).unwrap();
+        assert_eq!(session.undo_tree_head(), Some(b));
+
+        assert!(session.goto(c).unwrap());
+        let table = session.get_table(10).unwrap();
+        assert!(table.get(1).is_some());
+        assert!(table.get(2).is_none());
+        assert!(table.get(3).is_some());
+    }
+
+    #[test]
+    fn test_bounded_history_destroys_unreachable_items() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        static DESTROYED: AtomicUsize = AtomicUsize::new(0);
+
+        item_factory_mut().lock().unwrap().register_type(
+            102,
+            12,
+            Arc::new(|key| Arc::new(MyItem {
+                key,
+                item_type: 102,
+                table_type: 12,
+            })),
+            Arc::new(|_item| {
+                DESTROYED.fetch_add(1, Ordering::SeqCst);
+            }),
+        );
+        let factory = item_factory_mut();
+
+        let mut session = Session::new();
+        session.register_table(12, 102);
+        session.set_history_limit(HistoryLimit { max_steps: Some(2), max_bytes: None });
+
+        session.get_table_mut(12).unwrap().insert(1, factory);
+        session.commit_all().unwrap();
+        session.get_table_mut(12).unwrap().remove(1);
+        session.commit_all().unwrap();
+        let usage = session.history_memory_usage();
+        assert!(usage > 0);
+
+        // 삽입 기록이 버려져도 아이템은 삭제 기록이 아직 참조
+        session.get_table_mut(12).unwrap().insert(2, factory);
+        session.commit_all().unwrap();
+        assert_eq!(session.history().len(), 2);
+        assert_eq!(DESTROYED.load(Ordering::SeqCst), 0);
+
+        // 삭제 기록이 버려지면 아이템 1은 복원 불가 → destroy
+        session.get_table_mut(12).unwrap().insert(3, factory);
+        session.commit_all().unwrap();
+        assert_eq!(session.history().len(), 2);
+        assert_eq!(DESTROYED.load(Ordering::SeqCst), 1);
+
+        // undo 한 삽입은 새 커밋으로 redo 경로가 사라지면 destroy
+        session.undo_all().unwrap();
+        session.get_table_mut(12).unwrap().insert(4, factory);
+        session.commit_all().unwrap();
+        assert_eq!(DESTROYED.load(Ordering::SeqCst), 2);
+
+        // 메모리 한도
+        session.set_history_limit(HistoryLimit { max_steps: None, max_bytes: Some(1) });
+        assert_eq!(session.history().len(), 0);
+        assert!(session.history_memory_usage() < usage);
+        assert!(session.get_table(12).unwrap().get(4).is_some());
+    }
+
+    #[test]
+    fn test_destroy_ignores_snapshot_and_tree_copies() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        static DESTROYED: AtomicUsize = AtomicUsize::new(0);
+
+        item_factory_mut().lock().unwrap().register_type(
+            103,
+            13,
+            Arc::new(|key| Arc::new(MyItem {
+                key,
+                item_type: 103,
+                table_type: 13,
+            })),
+            Arc::new(|_item| {
+                DESTROYED.fetch_add(1, Ordering::SeqCst);
+            }),
+        );
+        let factory = item_factory_mut();
+
+        let mut session = Session::new();
+        session.register_table(13, 103);
+        session.enable_undo_tree();
+        session.set_history_limit(HistoryLimit { max_steps: Some(2), max_bytes: None });
+
+        session.get_table_mut(13).unwrap().insert(1, factory);
+        session.commit_all().unwrap();
+        let snapshot = session.snapshot(); // 버전 저장소와 트리 노드가 아이템 1을 함께 보관
+        session.get_table_mut(13).unwrap().remove(1);
+        session.commit_all().unwrap();
+        session.get_table_mut(13).unwrap().insert(2, factory);
+        session.commit_all().unwrap();
+        assert_eq!(DESTROYED.load(Ordering::SeqCst), 0);
+
+        // 삭제 기록이 마지막으로 버려질 때 destroy (스냅샷은 계속 읽을 수 있음)
+        session.get_table_mut(13).unwrap().insert(3, factory);
+        session.commit_all().unwrap();
+        assert_eq!(DESTROYED.load(Ordering::SeqCst), 1);
+        assert!(snapshot.get(13, 1).is_some());
+
+        // 테이블에 남아 있는 아이템은 기록을 모두 버려도 해제하지 않음
+        session.set_history_limit(HistoryLimit { max_steps: Some(0), max_bytes: None });
+        assert_eq!(session.history_step_count(), 0);
+        assert_eq!(DESTROYED.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_observers_receive_batches() {
+        register_my_item();
+        register_test_type(101, 11);
+        let factory = item_factory_mut();
+
+        let mut session = Session::new();
+        session.register_table(10, 100);
+        session.register_table(11, 101);
+
+        let all: Arc<Mutex<Vec<ChangeBatch>>> = Arc::new(Mutex::new(Vec::new()));
+        let only_11: Arc<Mutex<Vec<ChangeBatch>>> = Arc::new(Mutex::new(Vec::new()));
+        let sink = all.clone();
+        let all_id = session.subscribe(Arc::new(move |b| sink.lock().unwrap().push(b.clone())));
+        let sink = only_11.clone();
+        session.subscribe_table(11, Arc::new(move |b| sink.lock().unwrap().push(b.clone())));
+
+        // 두 테이블에 걸친 커밋 하나 = 묶음 하나
+        session.get_table_mut(10).unwrap().insert(1, factory);
+        session.get_table_mut(11).unwrap().insert(2, factory);
+        session.commit_labelled("add").unwrap();
+        {
+            let all = all.lock().unwrap();
+            assert_eq!(all.len(), 1);
+            assert_eq!(all[0].source, ChangeSource::Commit);
+            assert_eq!(all[0].label, "add");
+            assert_eq!(all[0].events.len(), 2);
+            let only_11 = only_11.lock().unwrap();
+            assert_eq!(only_11.len(), 1);
+            assert_eq!(only_11[0].events.len(), 1);
+            assert_eq!(only_11[0].events[0].key(), 2);
+        }
+
+        // undo 는 삽입을 삭제로, redo 는 다시 삽입으로 알림
+        session.undo_all().unwrap();
+        session.redo_all().unwrap();
+        {
+            let all = all.lock().unwrap();
+            assert_eq!(all.len(), 3);
+            assert_eq!(all[1].source, ChangeSource::Undo);
+            assert!(all[1].events.iter().all(|e| matches!(e.change, ChangeKind::Removed(_))));
+            assert_eq!(all[2].source, ChangeSource::Redo);
+            assert!(all[2].events.iter().all(|e| matches!(e.change, ChangeKind::Inserted(_))));
+        }
+
+        // 테이블 10만 바뀐 커밋은 테이블 11 구독자에게 전달되지 않음
+        session.get_table_mut(10).unwrap().insert(3, factory);
+        session.commit_all().unwrap();
+        assert_eq!(only_11.lock().unwrap().len(), 3);
+
+        assert!(session.unsubscribe(all_id));
+        session.get_table_mut(10).unwrap().insert(4, factory);
+        session.commit_all().unwrap();
+        assert_eq!(all.lock().unwrap().len(), 4);
+    }
+
+    #[test]
+    fn test_insert_and_remove_in_one_commit_cancel() {
+        register_my_item();
+        let factory = item_factory_mut();
+        let mut session = Session::new();
+        session.register_table(10, 100);
+
+        // 커밋 전에 넣고 지운 아이템은 기록에 남지 않음
+        let table = session.get_table_mut(10).unwrap();
+        table.insert(42, factory);
+        table.remove(42);
+        assert_eq!(table.tx.current_count(), 0);
+        session.commit_all().unwrap();
+        assert!(session.history().is_empty());
+
+        // 되돌릴 것이 없으므로 undo 후에도 없음
+        session.undo_all().unwrap();
+        assert!(session.get_table(10).unwrap().get(42).is_none());
+    }
+
+    #[test]
+    fn test_delta_keeps_net_effect_per_key() {
+        let cursor = |key| Cursor::new(Arc::new(MyItem { key, item_type: 100, table_type: 10 }));
+        let (a, b) = (cursor(1), cursor(1));
+        let mut delta: TxDeltaList = TxDeltaList::new();
+
+        // 삽입 후 수정 → 수정한 값의 삽입
+        delta.add(TxAction::Insert(a.clone()));
+        delta.add(TxAction::Modify { before: a.clone(), after: b.clone() });
+        assert!(matches!(&delta.actions[..], [TxAction::Insert(c)] if Arc::ptr_eq(&c.data, &b.data)));
+
+        // 삽입 후 삭제 → 변경 없음
+        delta.add(TxAction::Remove(b.clone()));
+        assert_eq!(delta.count(), 0);
+        assert!(delta.keys.is_empty());
+
+        // 삭제 후 다시 삽입 → 이전/이후 이미지를 가진 수정
+        delta.add(TxAction::Remove(a.clone()));
+        delta.add(TxAction::Insert(b.clone()));
+        assert!(matches!(&delta.actions[..], [TxAction::Modify { before, after }]
+            if Arc::ptr_eq(&before.data, &a.data) && Arc::ptr_eq(&after.data, &b.data)));
+    }
 }
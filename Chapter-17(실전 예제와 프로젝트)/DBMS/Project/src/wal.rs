@@ -0,0 +1,227 @@
+use std::fs::OpenOptions;
+use std::io;
+use std::path::Path;
+use std::sync::Mutex;
+
+use crate::define::TxAction;
+use crate::item_factory::ItemFactory;
+use crate::session::Session;
+use crate::tx_stream::{ACTION_FORMAT, FileTxStream, MemTxStream, TxStream};
+
+// 로그 파일 헤더
+const WAL_MAGIC: u32 = 0x4C57_584E; // "NXWL"
+const WAL_VERSION: u32 = ACTION_FORMAT; // 레코드 형식과 같은 번호, 1 부터 모두 읽을 수 있음
+const WAL_HEADER_LEN: u64 = 8;
+
+// 트랜잭션 마커
+const TX_BEGIN: u32 = 0xB0B0_B0B0;
+const TX_COMMIT: u32 = 0xC0C0_C0C0;
+
+/// 로그에 기록되는 액션 하나 (어느 테이블의 어떤 타입인지 포함)
+#[derive(Clone, Debug)]
+pub struct WalEntry {
+    pub table_type: u16,
+    pub item_type: u16,
+    pub action: TxAction,
+}
+
+/// Write-ahead log: 커밋된 트랜잭션을 begin/commit 마커로 감싸 파일 끝에 추가
+pub struct WriteAheadLog {
+    path: String,
+    stream: FileTxStream,
+    version: u32,
+    len: u64, // 마지막으로 온전히 기록된 위치 (실패한 레코드는 여기까지 잘라냄)
+}
+
+impl WriteAheadLog {
+    /// 로그 열기: 완료된 트랜잭션 목록을 반환하고 잘린 꼬리는 파일에서 제거
+    /// 이전 버전 로그는 읽기만 하고, upgrade 로 현재 버전으로 다시 써야 이어서 기록 가능
+    pub fn open(path: &str, factory: &Mutex<ItemFactory>) -> io::Result<(Self, Vec<Vec<WalEntry>>)> {
+        let (records, valid_len, version) = if Path::new(path).exists() {
+            Self::read_records(path, factory)?
+        } else {
+            (Vec::new(), 0, WAL_VERSION)
+        };
+
+        let file = OpenOptions::new().write(true).create(true).truncate(false).open(path)?;
+        file.set_len(valid_len)?;
+        file.sync_all()?;
+        drop(file);
+
+        let mut stream = FileTxStream::new_append(path)?;
+        if valid_len == 0 {
+            stream.write_u32(WAL_MAGIC);
+            stream.write_u32(WAL_VERSION);
+            stream.sync()?;
+        }
+
+        Ok((
+            WriteAheadLog {
+                path: path.to_string(),
+                stream,
+                version,
+                len: valid_len.max(WAL_HEADER_LEN),
+            },
+            records,
+        ))
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// 로그 비우기 (헤더만 남김): 체크포인트 후 호출
+    pub fn reset(&mut self) -> io::Result<()> {
+        self.stream.sync()?;
+        let file = OpenOptions::new().write(true).open(&self.path)?;
+        file.set_len(WAL_HEADER_LEN)?;
+        file.sync_all()?;
+        self.len = WAL_HEADER_LEN;
+        Ok(())
+    }
+
+    /// 트랜잭션 하나를 기록하고 디스크에 동기화 (아이템 페이로드는 session 으로 직렬화)
+    pub fn append(&mut self, entries: &[WalEntry], session: &Session) -> io::Result<()> {
+        if self.version != WAL_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "write-ahead log must be upgraded before appending",
+            ));
+        }
+        // 레코드를 먼저 메모리에 만들어 한 번에 기록
+        let mut record = MemTxStream::new();
+        if !Self::write_record(&mut record, entries, session) {
+            return Ok(());
+        }
+        self.stream.write_bytes(record.as_bytes());
+        if let Err(error) = self.stream.sync() {
+            self.discard_tail()?;
+            return Err(error);
+        }
+        self.len += record.as_bytes().len() as u64;
+        Ok(())
+    }
+
+    /// 실패한 레코드 버리기: 버퍼를 비우지 않고 닫은 뒤 파일을 마지막 정상 위치까지 자름
+    fn discard_tail(&mut self) -> io::Result<()> {
+        let stream = std::mem::replace(&mut self.stream, FileTxStream::new_append(&self.path)?);
+        stream.discard();
+        let file = OpenOptions::new().write(true).open(&self.path)?;
+        file.set_len(self.len)?;
+        file.sync_all()
+    }
+
+    /// 이전 버전 로그를 읽은 트랜잭션으로 현재 버전으로 다시 씀 (임시 파일에 쓴 뒤 교체)
+    /// session 은 records 를 재생한 세션 (페이로드 직렬화에 사용)
+    pub fn upgrade(&mut self, records: &[Vec<WalEntry>], session: &Session) -> io::Result<()> {
+        if self.version == WAL_VERSION {
+            return Ok(());
+        }
+
+        let tmp_path = format!("{}.tmp", self.path);
+        {
+            let mut stream = FileTxStream::new_write(&tmp_path)?;
+            stream.write_u32(WAL_MAGIC);
+            stream.write_u32(WAL_VERSION);
+            for record in records {
+                Self::write_record(&mut stream, record, session);
+            }
+            stream.sync()?;
+        }
+        self.stream.sync()?;
+        std::fs::rename(&tmp_path, &self.path)?;
+        self.stream = FileTxStream::new_append(&self.path)?;
+        self.version = WAL_VERSION;
+        self.len = std::fs::metadata(&self.path)?.len();
+        Ok(())
+    }
+
+    /// 트랜잭션 하나 기록 (취소된 액션 제외, 남은 액션이 없으면 false)
+    fn write_record(stream: &mut dyn TxStream, entries: &[WalEntry], session: &Session) -> bool {
+        let entries: Vec<&WalEntry> = entries
+            .iter()
+            .filter(|e| !matches!(e.action, TxAction::Cancelled))
+            .collect();
+        if entries.is_empty() {
+            return false;
+        }
+
+        stream.write_u32(TX_BEGIN);
+        stream.write_u32(entries.len() as u32);
+        for entry in &entries {
+            stream.write_u16(entry.table_type);
+            stream.write_u16(entry.item_type);
+            stream.write_action(&entry.action, session);
+        }
+        stream.write_u32(TX_COMMIT);
+        true
+    }
+
+    /// 완료된 트랜잭션, 마지막 정상 위치(바이트), 파일 버전을 읽음
+    fn read_records(path: &str, factory: &Mutex<ItemFactory>) -> io::Result<(Vec<Vec<WalEntry>>, u64, u32)> {
+        if std::fs::metadata(path)?.len() < WAL_HEADER_LEN {
+            return Ok((Vec::new(), 0, WAL_VERSION)); // 헤더 기록 중 중단
+        }
+
+        let mut stream = FileTxStream::new_read(path)?;
+        if stream.read_u32() != Some(WAL_MAGIC) {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "not a write-ahead log"));
+        }
+        let version = match stream.read_u32() {
+            Some(version @ 1..=WAL_VERSION) => version,
+            _ => return Err(io::Error::new(io::ErrorKind::InvalidData, "unsupported write-ahead log version")),
+        };
+
+        let mut records = Vec::new();
+        let mut valid_len = WAL_HEADER_LEN;
+        while let Some(record) = Self::read_record(&mut stream, factory, version)? {
+            records.push(record);
+            valid_len = stream.position().unwrap_or(valid_len);
+        }
+        Ok((records, valid_len, version))
+    }
+
+    /// 트랜잭션 하나 읽기: commit 마커까지 온전하지 않으면 None
+    fn read_record(
+        stream: &mut FileTxStream,
+        factory: &Mutex<ItemFactory>,
+        version: u32,
+    ) -> io::Result<Option<Vec<WalEntry>>> {
+        if stream.read_u32() != Some(TX_BEGIN) {
+            return Ok(None);
+        }
+        let Some(count) = stream.read_u32() else {
+            return Ok(None);
+        };
+
+        let mut entries = Vec::new();
+        for _ in 0..count {
+            let (Some(table_type), Some(item_type)) = (stream.read_u16(), stream.read_u16()) else {
+                return Ok(None);
+            };
+            let registered = factory
+                .lock()
+                .map(|f| f.get_type_info(item_type).is_some())
+                .unwrap_or(false);
+            if !registered {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("item_type {} is not registered in ItemFactory", item_type),
+                ));
+            }
+            let Some(action) = stream.try_read_action_format(item_type, factory, version)? else {
+                return Ok(None);
+            };
+            entries.push(WalEntry {
+                table_type,
+                item_type,
+                action,
+            });
+        }
+
+        if stream.read_u32() != Some(TX_COMMIT) {
+            return Ok(None);
+        }
+        Ok(Some(entries))
+    }
+}
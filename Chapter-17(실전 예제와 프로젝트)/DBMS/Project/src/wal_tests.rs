@@ -0,0 +1,323 @@
+#[cfg(test)]
+mod tests {
+    use std::fs::OpenOptions;
+    use std::io::Write;
+    use std::sync::Arc;
+    use crate::dbutil::get_db_temp_path;
+    use crate::define::TxAction;
+    use crate::item::DItem;
+    use crate::session::Session;
+    use crate::item_factory::item_factory_mut;
+    use crate::tx_stream::{FileTxStream, MemTxStream, StreamValue, TxStream};
+
+    #[derive(Debug)]
+    struct WalItem {
+        key: i32,
+    }
+
+    impl DItem for WalItem {
+        fn key(&self) -> i32 { self.key }
+        fn item_type(&self) -> u16 { 200 }
+        fn table_type(&self) -> u16 { 20 }
+        fn serialize(&self, _stream: &mut dyn crate::tx_stream::TxStream, _session: &crate::session::Session) {}
+    }
+
+    fn register_wal_item() {
+        let mut factory = item_factory_mut().lock().unwrap();
+        factory.register_type(
+            200,
+            20,
+            Arc::new(|key| Arc::new(WalItem { key })),
+            Arc::new(|_item| {}),
+        );
+        factory.register_deserializer(200, Arc::new(|key, _stream| Some(Arc::new(WalItem { key }))));
+    }
+
+    #[test]
+    fn test_wal_replay_discards_torn_tail() {
+        register_wal_item();
+        let factory = item_factory_mut();
+
+        let dir = get_db_temp_path();
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("wal_replay.log");
+        let _ = std::fs::remove_file(&path);
+        let path = path.to_str().unwrap();
+
+        {
+            let mut session = Session::open(path).unwrap();
+            session.register_table(20, 200);
+
+            let table = session.get_table_mut(20).unwrap();
+            table.insert(1, factory);
+            table.insert(2, factory);
+            session.commit_all().unwrap();
+
+            let table = session.get_table_mut(20).unwrap();
+            table.remove(1);
+            session.commit_all().unwrap();
+
+            // 커밋 전 종료 → 기록되지 않음
+            let table = session.get_table_mut(20).unwrap();
+            table.insert(3, factory);
+        }
+
+        // 기록 도중 중단된 트랜잭션 흉내
+        let valid_len = std::fs::metadata(path).unwrap().len();
+        let mut file = OpenOptions::new().append(true).open(path).unwrap();
+        file.write_all(&[0xB0, 0xB0, 0xB0, 0xB0, 0x05, 0x00]).unwrap();
+        drop(file);
+
+        {
+            let mut session = Session::open(path).unwrap();
+            let table = session.get_table(20).unwrap();
+            assert!(table.get(1).is_none());
+            assert!(table.get(2).is_some());
+            assert!(table.get(3).is_none());
+            assert_eq!(std::fs::metadata(path).unwrap().len(), valid_len);
+
+            let table = session.get_table_mut(20).unwrap();
+            table.insert(4, factory);
+            session.commit_all().unwrap();
+            session.undo_all().unwrap(); // undo 도 로그에 남음
+        }
+
+        let session = Session::open(path).unwrap();
+        let table = session.get_table(20).unwrap();
+        assert!(table.get(2).is_some());
+        assert!(table.get(4).is_none());
+    }
+
+    /// 헤더(버전 version)와 트랜잭션 하나로 된 로그 파일 작성
+    fn write_old_log(path: &str, version: u32, entries: impl Fn(&mut MemTxStream)) {
+        let mut stream = MemTxStream::new();
+        stream.write_u32(0x4C57_584E);
+        stream.write_u32(version);
+        stream.write_u32(0xB0B0_B0B0);
+        entries(&mut stream);
+        stream.write_u32(0xC0C0_C0C0);
+        std::fs::write(path, stream.into_bytes()).unwrap();
+    }
+
+    #[test]
+    fn test_wal_replays_version_1_log() {
+        register_wal_item();
+        let dir = get_db_temp_path();
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("wal_v1.log");
+        let path = path.to_str().unwrap();
+
+        // v1: 키, 상태, param_data, param (페이로드 없음)
+        write_old_log(path, 1, |stream| {
+            stream.write_u32(2);
+            for (key, param) in [(1, 10), (2, 20)] {
+                stream.write_u16(20);
+                stream.write_u16(200);
+                stream.write_u32(key);
+                stream.write_u8(0x01);
+                stream.write_u8(0);
+                stream.write_u32(param);
+            }
+        });
+
+        {
+            let mut session = Session::open(path).unwrap();
+            let table = session.get_table(20).unwrap();
+            assert_eq!(table.get(1).unwrap().param, 10);
+            assert_eq!(table.get(2).unwrap().param, 20);
+
+            // 현재 버전으로 다시 쓴 뒤 이어서 기록
+            let table = session.get_table_mut(20).unwrap();
+            table.remove(1);
+            session.commit_all().unwrap();
+        }
+
+        let session = Session::open(path).unwrap();
+        let table = session.get_table(20).unwrap();
+        assert!(table.get(1).is_none());
+        assert_eq!(table.get(2).unwrap().param, 20);
+    }
+
+    #[derive(Debug, PartialEq, DItem)]
+    #[ditem(item_type = 202, table_type = 22)]
+    struct Memo {
+        #[key]
+        id: i32,
+        text: String,
+    }
+
+    #[test]
+    fn test_wal_replays_version_2_log() {
+        assert!(Memo::register(item_factory_mut()));
+        let dir = get_db_temp_path();
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("wal_v2.log");
+        let path = path.to_str().unwrap();
+
+        // v2: v1 레코드 뒤에 길이 + 페이로드 (스키마 버전 없음 → 1 로 읽음)
+        write_old_log(path, 2, |stream| {
+            let mut payload = MemTxStream::new();
+            7i32.write_to(&mut payload);
+            "old".to_string().write_to(&mut payload);
+
+            stream.write_u32(1);
+            stream.write_u16(22);
+            stream.write_u16(202);
+            stream.write_u32(7);
+            stream.write_u8(0x01);
+            stream.write_u8(0);
+            stream.write_u32(0);
+            stream.write_u32(payload.as_bytes().len() as u32);
+            stream.write_bytes(payload.as_bytes());
+        });
+
+        // 현재 버전으로 다시 쓴 로그도 같은 내용
+        for _ in 0..2 {
+            let session = Session::open(path).unwrap();
+            let cursor = session.get_table(22).unwrap().get(7).unwrap();
+            assert_eq!(cursor.data.downcast_ref::<Memo>(), Some(&Memo { id: 7, text: "old".into() }));
+        }
+    }
+
+    #[derive(Debug)]
+    struct LocalItem {
+        key: i32,
+    }
+
+    impl DItem for LocalItem {
+        fn key(&self) -> i32 { self.key }
+        fn item_type(&self) -> u16 { 203 }
+        fn table_type(&self) -> u16 { 23 }
+        fn serialize(&self, _stream: &mut dyn crate::tx_stream::TxStream, _session: &crate::session::Session) {}
+    }
+
+    #[test]
+    fn test_types_without_deserializer_are_not_written() {
+        let factory = item_factory_mut();
+        factory.lock().unwrap().register_type(203, 23, Arc::new(|key| Arc::new(LocalItem { key })), Arc::new(|_item| {}));
+        let dir = get_db_temp_path();
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("wal_local.log");
+        let snapshot_path = dir.join("wal_local.snap");
+        let _ = std::fs::remove_file(&path);
+        let (path, snapshot_path) = (path.to_str().unwrap(), snapshot_path.to_str().unwrap());
+
+        // 로그가 없는 세션은 그대로 커밋
+        let mut session = Session::new();
+        session.register_table(23, 203);
+        session.get_table_mut(23).unwrap().insert(1, factory);
+        session.commit_all().unwrap();
+        assert_eq!(session.save_snapshot(snapshot_path).unwrap_err().kind(), std::io::ErrorKind::InvalidInput);
+
+        // 다시 읽을 수 없는 레코드는 기록하지 않고 커밋 실패
+        {
+            let mut session = Session::open(path).unwrap();
+            session.register_table(23, 203);
+            session.get_table_mut(23).unwrap().insert(1, factory);
+            assert_eq!(session.commit_all().unwrap_err().kind(), std::io::ErrorKind::InvalidInput);
+        }
+        assert!(Session::open(path).unwrap().get_table(23).is_none());
+    }
+
+    #[test]
+    fn test_cancelled_action_round_trips() {
+        let session = Session::new();
+        let mut stream = MemTxStream::new();
+        stream.write_action(&TxAction::Cancelled, &session);
+        stream.write_action(&TxAction::Cancelled, &session);
+
+        // 다음 레코드와 어긋나지 않고 하나씩 읽힘
+        let mut stream = MemTxStream::from_bytes(stream.into_bytes());
+        let factory = item_factory_mut();
+        for _ in 0..2 {
+            let action = stream.try_read_action(200, factory).unwrap();
+            assert!(matches!(action, Some(TxAction::Cancelled)));
+        }
+        assert_eq!(stream.remaining(), 0);
+        assert!(stream.try_read_action(200, factory).unwrap().is_none());
+    }
+
+    #[derive(Debug, PartialEq, DItem)]
+    #[ditem(item_type = 201, table_type = 21)]
+    struct Note {
+        #[key]
+        id: i32,
+        text: String,
+        tags: Vec<u32>,
+    }
+
+    fn text_of(session: &Session, key: i32) -> Option<String> {
+        let cursor = session.get_table(21)?.get(key)?;
+        Some(cursor.data.downcast_ref::<Note>()?.text.clone())
+    }
+
+    #[test]
+    fn test_wal_and_snapshot_keep_item_payloads() {
+        let factory = item_factory_mut();
+        assert!(Note::register(factory));
+
+        let dir = get_db_temp_path();
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("wal_payload.log");
+        let snapshot_path = dir.join("wal_payload.snap");
+        let _ = std::fs::remove_file(&path);
+        let path = path.to_str().unwrap();
+        let snapshot_path = snapshot_path.to_str().unwrap();
+
+        {
+            let mut session = Session::open(path).unwrap();
+            session.register_table(21, 201);
+            let table = session.get_table_mut(21).unwrap();
+            table.insert(1, factory);
+            table.modify(1, Arc::new(Note { id: 1, text: "first".into(), tags: vec![1, 2] }));
+            session.commit_all().unwrap();
+
+            let table = session.get_table_mut(21).unwrap();
+            table.modify(1, Arc::new(Note { id: 1, text: "second".into(), tags: vec![3] }));
+            session.commit_all().unwrap();
+        }
+
+        // 재생 후에도 내용 유지
+        {
+            let mut session = Session::open(path).unwrap();
+            assert_eq!(text_of(&session, 1).as_deref(), Some("second"));
+            let table = session.get_table_mut(21).unwrap();
+            table.modify(1, Arc::new(Note { id: 1, text: "third".into(), tags: vec![] }));
+            session.commit_all().unwrap();
+            session.undo_all().unwrap(); // 이전 이미지 페이로드로 기록
+        }
+
+        let session = Session::open(path).unwrap();
+        assert_eq!(text_of(&session, 1).as_deref(), Some("second"));
+        session.save_snapshot(snapshot_path).unwrap();
+        let loaded = Session::load_snapshot(snapshot_path).unwrap();
+        let cursor = loaded.get_table(21).unwrap().get(1).unwrap();
+        assert_eq!(cursor.data.downcast_ref::<Note>(), Some(&Note { id: 1, text: "second".into(), tags: vec![3] }));
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_write_errors_are_returned_from_sync() {
+        // /dev/full 은 모든 쓰기가 ENOSPC 로 실패
+        let mut stream = FileTxStream::new_append("/dev/full").unwrap();
+        for value in 0..10_000 {
+            stream.write_u32(value); // 버퍼를 넘겨도 패닉하지 않음
+        }
+        stream.flush();
+        assert_eq!(stream.sync().unwrap_err().kind(), std::io::ErrorKind::StorageFull);
+        stream.discard();
+    }
+
+    #[test]
+    fn test_read_stream_rejects_writes() {
+        let dir = get_db_temp_path();
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("wal_read_only.log");
+        std::fs::write(&path, [1, 2, 3, 4]).unwrap();
+
+        let mut stream = FileTxStream::new_read(path.to_str().unwrap()).unwrap();
+        stream.write_u8(1);
+        assert_eq!(stream.sync().unwrap_err().kind(), std::io::ErrorKind::Unsupported);
+        assert_eq!(stream.read_u32(), Some(u32::from_le_bytes([1, 2, 3, 4])));
+    }
+}
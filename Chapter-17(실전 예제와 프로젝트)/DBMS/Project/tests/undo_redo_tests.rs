@@ -1,9 +1,9 @@
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
-    use crate::item::{DItem, Cursor};
-    use crate::session::Session;
-    use crate::item_factory::{item_factory, item_factory_mut};
+    use dbms::item::DItem;
+    use dbms::session::Session;
+    use dbms::item_factory::item_factory_mut;
 
     #[derive(Debug)]
     struct MyItem {
@@ -16,7 +16,7 @@ mod tests {
         fn key(&self) -> i32 { self.key }
         fn item_type(&self) -> u16 { self.item_type }
         fn table_type(&self) -> u16 { self.table_type }
-        fn serialize(&self, _stream: &mut dyn crate::tx_stream::TxStream, _session: &crate::session::Session) {}
+        fn serialize(&self, _stream: &mut dyn dbms::tx_stream::TxStream, _session: &dbms::session::Session) {}
     }
 
     #[test]
@@ -43,6 +43,8 @@ mod tests {
         let cursor = table.insert(42, factory).unwrap();
         assert_eq!(cursor.key(), 42);
         assert!(table.get(42).is_some());
+        // 같은 트랜잭션의 삽입과 삭제는 서로 상쇄되므로 삽입을 먼저 커밋 (src/undo_redo_tests.rs 의 test_insert_and_remove_in_one_commit_cancel)
+        table.tx.commit();
 
         {
             table.remove(42);
@@ -53,12 +55,12 @@ mod tests {
         // 삭제
         {
             // Undo
-            session.undo_all();
+            session.undo_all().unwrap();
             assert!(session.get_table(10).unwrap().get(42).is_some());
         }
         {
             // Redo
-            session.redo_all();
+            session.redo_all().unwrap();
             assert!(session.get_table(10).unwrap().get(42).is_none());
         }
     }